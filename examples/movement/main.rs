@@ -1,16 +1,21 @@
 use bevy::prelude::*;
 use bevy_egui::EguiPlugin;
 use bevy_rapier2d::prelude::*;
-use move_vis::MoveVisPlugin;
+use move_vis::{
+    JumpArc, MoveVisConfig, MoveVisPlugin, MoveVisUiPlugin, MoveVisWorldPlugin, RapierSource,
+};
 
 use arena::ArenaPlugin;
-use player::PlayerPlugin;
+use player::{PlayerControl, PlayerPlugin};
 use ui::UiPlugin;
 
 mod arena;
 mod player;
 mod ui;
 
+#[cfg(test)]
+mod tests;
+
 // pub const SCALE: f32 = 100.0;
 // pub const SCALE: f32 = 10.0;
 pub const SCALE: f32 = 1.0;
@@ -34,32 +39,49 @@ fn main() {
         .add_plugin(ArenaPlugin)
         .add_plugin(PlayerPlugin)
         .add_plugin(UiPlugin)
-        .add_plugin(MoveVisPlugin)
-        .insert_resource(PlayerMovementSettings {
-            jump_height: JUMP_HEIGHT,
-            time_to_apex: TIME_TO_APEX,
-            run_speed: 500.0,
-            dash_speed: 10000.0,
-            // jump_impulse: 20000.0,
-            jump_power_coefficient: 20000.0,
-            coyote_time_ms: 100,
-            slide_factor: 60.0,
-            fall_factor: 100.0,
-            jump_break_factor: 200.0,
-            gravity_scale: DEFAULT_GRAVITY_SCALE,
-        })
-        .add_startup_system(setup)
+        .add_plugin(move_vis_plugin())
+        .add_plugin(MoveVisUiPlugin)
+        .add_plugin(MoveVisWorldPlugin)
+        .insert_resource(movement_settings())
+        .add_startup_system(setup_movement)
+        .add_startup_system(setup_camera)
         .run();
 }
 
-fn setup(
-    mut commands: Commands,
+fn move_vis_plugin() -> MoveVisPlugin<RapierSource> {
+    MoveVisPlugin::default()
+        .with_channel::<GravityScale>("Gravity Scale", |gravity_scale| gravity_scale.0)
+        .with_channel::<Damping>("Linear Damping", |damping| damping.linear_damping)
+        .with_channel::<PlayerControl>("Dashing", PlayerControl::dashing)
+}
+
+fn movement_settings() -> PlayerMovementSettings {
+    PlayerMovementSettings {
+        jump_height: JUMP_HEIGHT,
+        time_to_apex: TIME_TO_APEX,
+        run_speed: 500.0,
+        dash_speed: 10000.0,
+        // jump_impulse: 20000.0,
+        jump_power_coefficient: 20000.0,
+        coyote_time_ms: 100,
+        slide_factor: 60.0,
+        fall_factor: 100.0,
+        jump_break_factor: 200.0,
+        gravity_scale: DEFAULT_GRAVITY_SCALE,
+    }
+}
+
+fn setup_movement(
     mut rapier_config: ResMut<RapierConfiguration>,
     mut player_movement_settings: ResMut<PlayerMovementSettings>,
+    mut move_vis_config: ResMut<MoveVisConfig>,
 ) {
-    set_gravity(&mut rapier_config, &*player_movement_settings);
-    set_jump_power_coefficient(&rapier_config, &mut *player_movement_settings);
+    set_gravity(&mut rapier_config, &player_movement_settings);
+    set_jump_power_coefficient(&rapier_config, &mut player_movement_settings);
+    set_jump_arc(&mut move_vis_config, &player_movement_settings);
+}
 
+fn setup_camera(mut commands: Commands) {
     let camera = Camera2dBundle::default();
     commands.spawn(camera);
 }
@@ -73,6 +95,17 @@ fn set_gravity(
         / player_movement_settings.time_to_apex.powf(2.0);
 }
 
+/// the jump the settings are designed for, drawn over every recorded one
+fn set_jump_arc(
+    move_vis_config: &mut MoveVisConfig,
+    player_movement_settings: &PlayerMovementSettings,
+) {
+    move_vis_config.jump_arc = Some(JumpArc::new(
+        player_movement_settings.jump_height,
+        player_movement_settings.time_to_apex,
+    ));
+}
+
 /// what is the initial jump velocity?
 /// 50 is a multiplier.  Say the expected value of jump_power_coefficient is 20,000 and
 /// (2.0 * rapier_config.gravity.y.abs() * JUMP_HEIGHT).sqrt() gives 400.0
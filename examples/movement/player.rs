@@ -4,13 +4,14 @@ use std::time::Duration;
 use bevy::{prelude::*, utils::Instant};
 use bevy_rapier2d::prelude::*;
 
-use move_vis::TrackMovement;
+use move_vis::{MoveVisMarker, TrackMovement, TrackState, TrackStatePlugin};
 
 use crate::PlayerMovementSettings;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 enum Direction {
     Left = -1,
+    #[default]
     Neutral = 0,
     Right = 1,
 }
@@ -25,12 +26,6 @@ impl Direction {
     }
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::Neutral
-    }
-}
-
 impl TryFrom<f32> for Direction {
     type Error = ();
 
@@ -64,6 +59,7 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DashInput>()
+            .add_plugin(TrackStatePlugin::<JumpStatus>::default())
             .add_startup_system(setup_player)
             .add_system(check_standing)
             .add_system(dash.after(check_standing))
@@ -91,6 +87,7 @@ fn dash(
     mut query: Query<(Entity, &mut Velocity, &mut GravityScale, &mut PlayerControl)>,
     rapier_context: Res<RapierContext>,
     player_movement_settings: Res<PlayerMovementSettings>,
+    mut markers: EventWriter<MoveVisMarker>,
 ) {
     for (player_entity, mut velocity, mut gravity_scale, mut player_control) in query.iter_mut() {
         let dir = if input.just_pressed(KeyCode::A) || input.just_pressed(KeyCode::Left) {
@@ -121,6 +118,14 @@ fn dash(
                         time.delta_seconds(),
                     );
 
+                    if !player_control.dashing {
+                        markers.send(MoveVisMarker {
+                            entity: player_entity,
+                            label: "dash start".to_string(),
+                            color: Color::ORANGE,
+                        });
+                    }
+
                     player_control.dashing = true;
 
                     *gravity_scale = GravityScale(0.0);
@@ -133,6 +138,12 @@ fn dash(
         if dash_input.input_timer.finished() && player_control.dashing {
             player_control.dashing = false;
 
+            markers.send(MoveVisMarker {
+                entity: player_entity,
+                label: "dash end".to_string(),
+                color: Color::ORANGE,
+            });
+
             // if dash is finished, reset gravity scale otherwise, when doing a dash jump
             // and holding space, the player will fly
             *gravity_scale = GravityScale(player_movement_settings.gravity_scale);
@@ -143,8 +154,9 @@ fn dash(
     }
 }
 
-#[derive(Debug)]
-enum JumpStatus {
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JumpStatus {
+    #[default]
     CanJump,
     InitiateJump,
     InitiateWallJump,
@@ -168,7 +180,10 @@ fn setup_player(mut commands: Commands, player_movement_settings: Res<PlayerMove
         ExternalForce::default(),
         ColliderMassProperties::Density(1.0),
         Velocity::zero(),
+        TransformBundle::default(),
+        Name::new("Player"),
         TrackMovement,
+        TrackState(JumpStatus::default()),
         PlayerControl::new(),
     ));
 }
@@ -185,6 +200,15 @@ pub struct PlayerControl {
 }
 
 impl PlayerControl {
+    /// 1 while dashing, recorded as a move_vis channel
+    pub fn dashing(&self) -> f32 {
+        if self.dashing {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
     fn new() -> Self {
         Self {
             last_stood_normal: Vec2::Y,
@@ -237,10 +261,16 @@ fn get_standing_normal(
 fn jump(
     time: Res<Time>,
     input: Res<Input<KeyCode>>,
-    mut query: Query<(Entity, &mut Velocity, &mut PlayerControl)>,
+    mut query: Query<(
+        Entity,
+        &mut Velocity,
+        &mut PlayerControl,
+        &mut TrackState<JumpStatus>,
+    )>,
     player_movement_settings: Res<PlayerMovementSettings>,
     rapier_context: Res<RapierContext>,
     rapier_config: Res<RapierConfiguration>,
+    mut markers: EventWriter<MoveVisMarker>,
 ) {
     let pressed_jump = input.pressed(KeyCode::Space);
 
@@ -249,7 +279,7 @@ fn jump(
     // let jump_impulse = 1.0;   // SCALE = 100.0
     // let jump_impulse = player_movement_settings.jump_impulse / SCALE.powf(2.0);
 
-    for (player_entity, mut velocity, mut player_control) in query.iter_mut() {
+    for (player_entity, mut velocity, mut player_control, mut jump_state) in query.iter_mut() {
         // find a normal of the standing ground where the player stands on
         let mut standing_normal = get_standing_normal(&rapier_context, &player_entity);
 
@@ -269,6 +299,15 @@ fn jump(
             };
         }
 
+        let mut mark = |label: &str, color| {
+            markers.send(MoveVisMarker {
+                entity: player_entity,
+                label: label.to_string(),
+                color,
+            })
+        };
+
+        let was_wall_sliding = player_control.wall_sliding;
         player_control.wall_sliding = false;
 
         if let Some(normal) = standing_normal {
@@ -276,6 +315,11 @@ fn jump(
             // 1. on a ground
             // 2. on wall grab
             if normal.x.abs() == 1.0 || normal.y == 1.0 {
+                // still rising means the jump has only just started and has not left the ground yet
+                if player_control.jumping && !player_control.rising {
+                    mark("landed", Color::GREEN);
+                }
+
                 player_control.jumping = false;
                 player_control.wall_jumping = false;
             }
@@ -316,11 +360,17 @@ fn jump(
             }
         })();
 
+        jump_state.0 = jump_status;
+
         match jump_status {
             JumpStatus::CanJump => {
                 player_control.rising = false;
             }
             JumpStatus::InitiateJump => {
+                if !player_control.rising {
+                    mark("jump", Color::CYAN);
+                }
+
                 velocity.linvel += Vec2::Y * player_movement_settings.jump_power_coefficient;
 
                 player_control.rising = true;
@@ -345,6 +395,10 @@ fn jump(
                 player_control.rising = false;
             }
             JumpStatus::WallSliding => {
+                if !was_wall_sliding {
+                    mark("wall grab", Color::PURPLE);
+                }
+
                 player_control.wall_sliding = true;
                 player_control.rising = false;
 
@@ -355,6 +409,8 @@ fn jump(
                 velocity.linvel.y = 0.0;
             }
             JumpStatus::InitiateWallJump => {
+                mark("wall jump", Color::CYAN);
+
                 player_control.wall_jumping = true;
 
                 velocity.linvel.x = Vec2::X.x * player_movement_settings.run_speed;
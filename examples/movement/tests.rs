@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+use move_vis::{
+    ContactKind, History, InputScript, JumpArc, MoveVisConfig, MovementTest, RecordTrigger,
+};
+
+use crate::{
+    arena::ArenaPlugin,
+    move_vis_plugin, movement_settings,
+    player::{JumpStatus, PlayerPlugin},
+    setup_movement, JUMP_HEIGHT, TIME_TO_APEX,
+};
+
+struct TestPlugin;
+
+impl Plugin for TestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(ArenaPlugin)
+            .add_plugin(PlayerPlugin)
+            .insert_resource(movement_settings())
+            .add_startup_system(setup_movement);
+    }
+}
+
+// the player spawns in the air, let it land before scripting anything
+fn landed() -> (MovementTest, History) {
+    landed_with(MoveVisConfig::default())
+}
+
+fn landed_with(move_vis_config: MoveVisConfig) -> (MovementTest, History) {
+    let mut test = MovementTest::new(TestPlugin, move_vis_plugin()).with_config(move_vis_config);
+    let history = test.run(&InputScript::new(), 1.0);
+
+    (test, history)
+}
+
+fn last(values: &[Vec3]) -> Vec3 {
+    *values.last().expect("nothing recorded")
+}
+
+#[test]
+fn records_under_the_designed_jump_arc() {
+    let (_, history) = landed();
+
+    assert_eq!(
+        history.config.expect("no config recorded").jump_arc,
+        Some(JumpArc::new(JUMP_HEIGHT, TIME_TO_APEX))
+    );
+}
+
+#[test]
+fn records_from_spawn() {
+    let (_, history) = landed();
+
+    assert!(!history.time.is_empty());
+    // resting on the ground, below where the player spawned
+    assert!(last(&history.distance).y < 0.0);
+    assert!(last(&history.velocity).length() < 1.0);
+}
+
+#[test]
+fn holding_d_runs_right() {
+    let (mut test, before) = landed();
+    let start = last(&before.distance);
+
+    let history = test.run(&InputScript::new().hold(KeyCode::D, 1.0, 0.5), 0.5);
+
+    assert!(last(&history.distance).x > start.x + 10.0);
+    assert!(last(&history.velocity).x > 0.0);
+}
+
+#[test]
+fn holding_a_runs_left() {
+    let (mut test, before) = landed();
+    let start = last(&before.distance);
+
+    let history = test.run(&InputScript::new().hold(KeyCode::A, 1.0, 0.5), 0.5);
+
+    assert!(last(&history.distance).x < start.x - 10.0);
+    assert!(last(&history.velocity).x < 0.0);
+}
+
+#[test]
+fn space_jumps_and_lands() {
+    let (mut test, before) = landed();
+    let ground = last(&before.distance).y;
+
+    let history = test.run(&InputScript::new().press(KeyCode::Space, 1.2), 2.0);
+
+    let apex = history
+        .distance
+        .iter()
+        .map(|distance| distance.y)
+        .fold(f32::MIN, f32::max);
+
+    assert!(apex > ground + 1.0);
+    assert!(history.velocity.iter().any(|velocity| velocity.y > 0.0));
+    assert!((last(&history.distance).y - ground).abs() < 1.0);
+}
+
+#[test]
+fn jump_metrics() {
+    let (mut test, _) = landed();
+
+    let script = InputScript::new()
+        .hold(KeyCode::D, 1.0, 2.0)
+        .press(KeyCode::Space, 1.2);
+    let jumps = test.run(&script, 2.0).jumps(1.0);
+
+    assert_eq!(jumps.len(), 1);
+
+    let jump = &jumps[0];
+    assert!(jump.apex_height > 1.0);
+    assert!(0.0 < jump.time_to_apex && jump.time_to_apex < jump.air_time);
+    assert!(jump.horizontal_range > 0.0);
+    assert!(jump.landing_velocity.y < 0.0);
+}
+
+#[test]
+fn jump_markers() {
+    let (mut test, _) = landed();
+
+    let history = test.run(&InputScript::new().press(KeyCode::Space, 1.2), 2.0);
+    let labels: Vec<_> = history
+        .markers
+        .iter()
+        .map(|marker| marker.label.as_str())
+        .collect();
+
+    assert_eq!(labels, ["jump", "landed"]);
+}
+
+#[test]
+fn jump_states() {
+    let (mut test, _) = landed();
+
+    let history = test.run(&InputScript::new().hold(KeyCode::Space, 1.2, 0.2), 2.0);
+    let mut states: Vec<_> = history
+        .state_lane::<JumpStatus>()
+        .expect("no jump states")
+        .iter()
+        .flatten()
+        .map(String::as_str)
+        // the player spawns in the air, skip the fall onto the ground
+        .skip_while(|&state| state != "CanJump")
+        .collect();
+    states.dedup();
+
+    assert_eq!(
+        states,
+        [
+            "CanJump",
+            "InitiateJump",
+            "GoingUp",
+            "StoppingUp",
+            "GoingDown",
+            "CanJump"
+        ]
+    );
+}
+
+#[test]
+fn input_lanes() {
+    let (mut test, _) = landed();
+
+    let history = test.run(&InputScript::new().hold(KeyCode::Space, 1.2, 0.2), 1.0);
+    let pressed: Vec<_> = history
+        .time
+        .iter()
+        .zip(&history.inputs["Key Space"])
+        .filter(|(_, pressed)| **pressed == Some(true))
+        .map(|(&t, _)| t)
+        .collect();
+
+    // within a frame of the script, held for 0.2s at 60 frames per second
+    assert!((pressed[0] - 1.2).abs() <= 1.0 / 60.0);
+    assert_eq!(pressed.len(), 12);
+}
+
+#[test]
+fn extra_input_lanes() {
+    let (mut test, _) = landed_with(MoveVisConfig {
+        input_lanes: vec![RecordTrigger::Key(KeyCode::D)],
+        ..default()
+    });
+
+    let history = test.run(&InputScript::new().hold(KeyCode::D, 1.0, 0.5), 1.0);
+
+    assert!(history.inputs["Key D"].contains(&Some(true)));
+}
+
+#[test]
+fn channels() {
+    let (_, history) = landed();
+
+    for name in ["Gravity Scale", "Linear Damping", "Dashing"] {
+        assert_eq!(history.channels[name].len(), history.time.len(), "{name}");
+    }
+    assert_eq!(
+        history.channels["Gravity Scale"].last(),
+        Some(&Some(crate::DEFAULT_GRAVITY_SCALE))
+    );
+}
+
+#[test]
+fn contacts() {
+    let (mut test, before) = landed();
+
+    let grounded = before
+        .contacts
+        .last()
+        .cloned()
+        .flatten()
+        .expect("no contact");
+    assert_eq!(grounded.kind(), ContactKind::Ground);
+    assert_eq!(grounded.count, 1);
+    assert!(grounded.normal_angle().unwrap() < 1.0);
+
+    let history = test.run(&InputScript::new().press(KeyCode::Space, 1.2), 0.3);
+    let last = history
+        .contacts
+        .last()
+        .cloned()
+        .flatten()
+        .expect("no contact");
+    assert_eq!(last.kind(), ContactKind::Air);
+    assert_eq!(last.count, 0);
+}
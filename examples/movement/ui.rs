@@ -2,9 +2,9 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContext};
 use bevy_rapier2d::prelude::*;
 
-use move_vis::make_slider;
+use move_vis::{make_slider, MoveVisConfig};
 
-use crate::{set_gravity, set_jump_power_coefficient, PlayerMovementSettings};
+use crate::{set_gravity, set_jump_arc, set_jump_power_coefficient, PlayerMovementSettings};
 
 pub struct UiPlugin;
 
@@ -18,6 +18,7 @@ fn ui_system(
     mut egui_context: ResMut<EguiContext>,
     mut player_movement_settings: ResMut<PlayerMovementSettings>,
     mut rapier_config: ResMut<RapierConfiguration>,
+    mut move_vis_config: ResMut<MoveVisConfig>,
 ) {
     egui::Window::new("Physical Properties Tweaking").show(egui_context.ctx_mut(), |ui| {
         let player_movement_settings = &mut *player_movement_settings;
@@ -75,5 +76,6 @@ fn ui_system(
 
         set_gravity(&mut rapier_config, player_movement_settings);
         set_jump_power_coefficient(&rapier_config, &mut *player_movement_settings);
+        set_jump_arc(&mut move_vis_config, player_movement_settings);
     });
 }
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+use crate::{push_lane, record_history, History, TrackMovement};
+
+/// adds the systems recording one channel, see `MoveVisPlugin::with_channel`
+pub(crate) type ChannelRegistration = Box<dyn Fn(&mut App) + Send + Sync>;
+
+pub(crate) fn channel_registration<C: Component>(
+    name: &str,
+    read: fn(&C) -> f32,
+) -> ChannelRegistration {
+    let name = name.to_string();
+
+    Box::new(move |app| {
+        let name = name.clone();
+
+        app.add_system(
+            (move |mut query: Query<(&C, &mut History), With<TrackMovement>>| {
+                for (component, mut history) in query.iter_mut() {
+                    let History { time, channels, .. } = &mut *history;
+
+                    push_lane(
+                        channels.entry(name.clone()).or_default(),
+                        time.len(),
+                        read(component),
+                    );
+                }
+            })
+            .after(record_history),
+        );
+    })
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+/// What a tracked entity was touching at a sample, recorded by the rapier sources
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Contact {
+    /// how many colliders were touched
+    pub count: usize,
+    /// pointing away from what was touched, the most upward one of all contacts
+    pub normal: Option<Vec3>,
+}
+
+/// Where a tracked entity stood, see [`Contact::kind`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContactKind {
+    Ground,
+    /// touching something from the side, but nothing from below
+    Wall,
+    /// touching something from above only
+    Ceiling,
+    Air,
+}
+
+impl Contact {
+    /// any contact with an upward normal, slopes included, counts as ground, and one with only
+    /// downward normals as ceiling
+    pub fn kind(&self) -> ContactKind {
+        match self.normal {
+            Some(normal) if normal.y > 0.001 => ContactKind::Ground,
+            Some(normal) if normal.y < -0.001 => ContactKind::Ceiling,
+            Some(_) => ContactKind::Wall,
+            None => ContactKind::Air,
+        }
+    }
+
+    /// degrees between the normal and straight up, 0 on flat ground and 90 on a wall
+    pub fn normal_angle(&self) -> Option<f32> {
+        self.normal
+            .map(|normal| normal.angle_between(Vec3::Y).to_degrees())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_from_the_normal() {
+        let kind = |normal| Contact { count: 1, normal }.kind();
+
+        assert_eq!(kind(Some(Vec3::new(0.5, 0.5, 0.0))), ContactKind::Ground);
+        assert_eq!(kind(Some(Vec3::X)), ContactKind::Wall);
+        assert_eq!(kind(Some(Vec3::NEG_Y)), ContactKind::Ceiling);
+        assert_eq!(kind(None), ContactKind::Air);
+    }
+}
@@ -0,0 +1,96 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+
+use crate::History;
+
+impl History {
+    /// Write the current recording as CSV, one row per sample
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "time,position_x,position_y,position_z,velocity_x,velocity_y,velocity_z"
+        )?;
+
+        for ((t, p), v) in self.time.iter().zip(&self.distance).zip(&self.velocity) {
+            writeln!(
+                writer,
+                "{t},{},{},{},{},{},{}",
+                p.x, p.y, p.z, v.x, v.y, v.z
+            )?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// `<dir>/<name>_<entity>.<extension>`, with anything but letters, digits, `-` and `_` in the name
+/// replaced, the entity keeps entities sharing a name from overwriting each other's files
+pub(crate) fn file_path(dir: &Path, entity: Entity, name: &str, extension: &str) -> PathBuf {
+    let file_name: String = format!("{name}_{entity:?}")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    dir.join(file_name).with_extension(extension)
+}
+
+/// Write one CSV file per `(entity, name, history)` into `dir`, errors are logged
+pub fn export_csv<'a>(dir: &Path, histories: impl Iterator<Item = (Entity, String, &'a History)>) {
+    for (entity, name, history) in histories {
+        let path = file_path(dir, entity, &name, "csv");
+
+        match File::create(&path).and_then(|file| history.write_csv(BufWriter::new(file))) {
+            Ok(()) => info!("exported {}", path.display()),
+            Err(e) => error!("failed to export {}: {e}", path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_csv_writes_a_row_per_sample() {
+        let mut history = History::default();
+        history.push(0.0, Vec3::new(1.0, 2.0, 0.0), Vec3::ZERO);
+        history.push(0.5, Vec3::new(1.5, 2.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+
+        let mut csv = Vec::new();
+        history.write_csv(&mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "time,position_x,position_y,position_z,velocity_x,velocity_y,velocity_z\n\
+             0,1,2,0,0,0,0\n\
+             0.5,1.5,2,0,1,0,0\n"
+        );
+    }
+
+    #[test]
+    fn file_path_replaces_odd_characters() {
+        assert_eq!(
+            file_path(Path::new("out"), Entity::from_raw(3), "Player (1)", "csv"),
+            Path::new("out/Player__1__3v0.csv")
+        );
+    }
+
+    #[test]
+    fn file_path_tells_entities_sharing_a_name_apart() {
+        let dir = Path::new("out");
+
+        assert_ne!(
+            file_path(dir, Entity::from_raw(3), "Player", "csv"),
+            file_path(dir, Entity::from_raw(4), "Player", "csv")
+        );
+    }
+}
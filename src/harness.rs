@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use bevy::{core::CorePlugin, hierarchy::HierarchyPlugin, prelude::*, transform::TransformPlugin};
+use bevy_rapier2d::prelude::*;
+
+use crate::{History, MoveVisConfig, MoveVisPlugin, RapierSource, RecordMode, TrackMovement};
+
+/// A timed script of key presses, replayed by [`MovementTest`]
+///
+/// ```ignore
+/// // hold D for 0.5s, press Space at 0.2s
+/// InputScript::new().hold(KeyCode::D, 0.0, 0.5).press(KeyCode::Space, 0.2)
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct InputScript {
+    // key, pressed at, released at, in seconds
+    holds: Vec<(KeyCode, f32, f32)>,
+}
+
+impl InputScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// hold `key` from `at` for `secs` seconds
+    pub fn hold(mut self, key: KeyCode, at: f32, secs: f32) -> Self {
+        self.holds.push((key, at, at + secs));
+        self
+    }
+
+    /// press `key` at `at` and release it on the next frame
+    pub fn press(self, key: KeyCode, at: f32) -> Self {
+        self.hold(key, at, 0.0)
+    }
+
+    fn pressed(&self, key: KeyCode, secs: f32, timestep: f32) -> bool {
+        self.holds.iter().any(|&(hold_key, from, until)| {
+            // a press shorter than a frame still lasts one frame
+            hold_key == key && from <= secs && secs < until.max(from + timestep)
+        })
+    }
+}
+
+/// A headless [`App`] with rapier, [`MoveVisPlugin`] and a user controller, stepped with a
+/// fixed timestep so that recordings are reproducible in `cargo test`
+pub struct MovementTest {
+    pub app: App,
+    timestep: Duration,
+    elapsed: Duration,
+}
+
+impl MovementTest {
+    /// `controller` is the plugin moving the entity with [`TrackMovement`], e.g. a `PlayerPlugin`,
+    /// recorded by `move_vis_plugin` with whatever channels the game adds to it
+    pub fn new(controller: impl Plugin, move_vis_plugin: MoveVisPlugin<RapierSource>) -> Self {
+        let mut app = App::new();
+
+        app.add_plugin(CorePlugin::default())
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .insert_resource(Time::default())
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(recording_config(MoveVisConfig::default()))
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
+            .add_plugin(move_vis_plugin)
+            .add_plugin(controller);
+
+        Self {
+            app,
+            timestep: Duration::from_secs_f32(1.0 / 60.0),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Record with `move_vis_config`, e.g. to add `input_lanes`. Its `mode` and `track_duration`
+    /// are overridden, a test always records from the very first frame on.
+    pub fn with_config(mut self, move_vis_config: MoveVisConfig) -> Self {
+        self.app.insert_resource(recording_config(move_vis_config));
+        self
+    }
+
+    /// 1/60 of a second by default
+    pub fn with_timestep(mut self, timestep: Duration) -> Self {
+        self.timestep = timestep;
+        self
+    }
+
+    /// Replay `script` for `secs` seconds, continuing from any earlier run, and return the
+    /// [`History`] of the first entity with [`TrackMovement`]
+    pub fn run(&mut self, script: &InputScript, secs: f32) -> History {
+        let startup = self.app.world.resource::<Time>().startup();
+        let until = self.elapsed + Duration::from_secs_f32(secs);
+
+        while self.elapsed < until {
+            let now = self.elapsed.as_secs_f32();
+            let timestep = self.timestep.as_secs_f32();
+
+            let mut input = self.app.world.resource_mut::<Input<KeyCode>>();
+            input.clear();
+
+            for &(key, ..) in &script.holds {
+                match (script.pressed(key, now, timestep), input.pressed(key)) {
+                    (true, false) => input.press(key),
+                    (false, true) => input.release(key),
+                    _ => (),
+                }
+            }
+
+            self.elapsed += self.timestep;
+
+            self.app
+                .world
+                .resource_mut::<Time>()
+                .update_with_instant(startup + self.elapsed);
+
+            self.app.update();
+        }
+
+        self.history()
+    }
+
+    pub fn history(&mut self) -> History {
+        self.app
+            .world
+            .query_filtered::<&History, With<TrackMovement>>()
+            .iter(&self.app.world)
+            .next()
+            .expect("no entity with TrackMovement")
+            .clone()
+    }
+}
+
+/// `move_vis_config` recording from the very first frame the entity exists, without ever stopping
+fn recording_config(move_vis_config: MoveVisConfig) -> MoveVisConfig {
+    MoveVisConfig {
+        mode: RecordMode::Rolling,
+        track_duration: Duration::MAX,
+        ..move_vis_config
+    }
+}
@@ -1,27 +1,124 @@
-use std::ops::RangeInclusive;
+use std::collections::{BTreeMap, VecDeque};
+use std::marker::PhantomData;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use bevy::prelude::*;
-use bevy_egui::{
-    egui::{
-        self,
-        plot::{Legend, Line, Plot, PlotPoints},
-    },
-    EguiContext,
-};
 
-#[cfg(feature = "bevy_rapier")]
-use bevy_rapier2d::prelude::Velocity;
+use channel::{channel_registration, ChannelRegistration};
 
-#[derive(Resource)]
-struct MoveVisConfig {
-    track_duration: Duration,
+mod channel;
+mod contact;
+mod export;
+#[cfg(feature = "test-harness")]
+mod harness;
+mod metrics;
+#[cfg(feature = "serde")]
+mod save;
+mod source;
+mod state;
+mod trigger;
+#[cfg(feature = "egui")]
+mod ui;
+#[cfg(feature = "sprites")]
+mod world;
+
+pub use contact::*;
+pub use export::export_csv;
+#[cfg(feature = "test-harness")]
+pub use harness::*;
+pub use metrics::*;
+#[cfg(feature = "serde")]
+pub use save::*;
+pub use source::*;
+pub use state::*;
+pub use trigger::*;
+#[cfg(feature = "egui")]
+pub use ui::*;
+#[cfg(feature = "sprites")]
+pub use world::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum RecordMode {
+    /// record for `track_duration` once triggered, then wait for the next trigger
+    #[default]
+    OneShot,
+    /// always record, keeping only the last `track_duration` (and up to an eighth more) like an
+    /// oscilloscope
+    Rolling,
+}
+
+#[derive(Clone, Debug, Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MoveVisConfig {
+    pub mode: RecordMode,
+    pub track_duration: Duration,
+    // only useful for 3D games, the Z component is always 0 in 2D
+    pub plot_z: bool,
+    /// colour the trajectory plot by speed instead of drawing it in one colour
+    pub color_by_speed: bool,
+    /// holding any of these starts a recording
+    pub triggers: Vec<RecordTrigger>,
+    /// inputs recorded along with the triggers, drawn as lanes under the velocity plot
+    pub input_lanes: Vec<RecordTrigger>,
+    /// how many completed recordings are kept and drawn as ghosts under the current one
+    pub ghosts: usize,
+    /// where "Export CSV" and "Save Recordings" write a file per tracked entity
+    pub export_dir: PathBuf,
+    /// vertical speed above which a tracked entity counts as airborne when detecting jumps
+    pub airborne_speed: f32,
+    /// the ideal jump to compare every detected one against on the distance plot
+    pub jump_arc: Option<JumpArc>,
+    #[cfg(feature = "serde")]
+    pub save_format: SaveFormat,
+    /// draw every recording in the game world as a breadcrumb trail, see `MoveVisWorldPlugin`
+    #[cfg(feature = "sprites")]
+    pub trail: bool,
+    /// seconds until a breadcrumb has faded out
+    #[cfg(feature = "sprites")]
+    pub trail_lifetime: f32,
+    #[cfg(feature = "sprites")]
+    pub trail_size: f32,
+    /// draw the velocity as an arrow in the game world, see `MoveVisWorldPlugin`
+    #[cfg(feature = "sprites")]
+    pub velocity_arrows: bool,
+    /// an arrow is drawn at every `arrow_every`th sample
+    #[cfg(feature = "sprites")]
+    pub arrow_every: usize,
+    /// the length of an arrow per unit of speed
+    #[cfg(feature = "sprites")]
+    pub arrow_scale: f32,
 }
 
 impl Default for MoveVisConfig {
     fn default() -> Self {
         Self {
+            mode: RecordMode::default(),
             track_duration: Duration::from_secs(2),
+            plot_z: cfg!(feature = "bevy_rapier3d"),
+            color_by_speed: false,
+            triggers: RecordTrigger::defaults(),
+            input_lanes: Vec::new(),
+            ghosts: 3,
+            export_dir: PathBuf::from("."),
+            airborne_speed: 1.0,
+            jump_arc: None,
+            #[cfg(feature = "serde")]
+            save_format: SaveFormat::default(),
+            #[cfg(feature = "sprites")]
+            trail: false,
+            #[cfg(feature = "sprites")]
+            trail_lifetime: 2.0,
+            #[cfg(feature = "sprites")]
+            trail_size: 3.0,
+            #[cfg(feature = "sprites")]
+            velocity_arrows: false,
+            #[cfg(feature = "sprites")]
+            arrow_every: 5,
+            #[cfg(feature = "sprites")]
+            arrow_scale: 0.1,
         }
     }
 }
@@ -29,23 +126,342 @@ impl Default for MoveVisConfig {
 #[derive(Clone, Component, Default)]
 pub struct TrackMovement;
 
-#[derive(Component, Debug, Default)]
+#[derive(Clone, Component, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct History {
+    /// seconds elapsed since the recording started, one entry per sample
+    pub time: Vec<f32>,
     pub velocity: Vec<Vec3>,
     pub distance: Vec<Vec3>,
+    /// derived from `velocity` by differencing successive samples over their time step,
+    /// `None` for the first sample as there is nothing before it to difference against
+    pub acceleration: Vec<Option<Vec3>>,
+    /// derived from `acceleration` the same way, `None` for the first two samples
+    pub jerk: Vec<Option<Vec3>>,
+    /// every [`TrackState`] by the full name of its type, see `History::state_lane`,
+    /// one entry per sample, `None` for samples recorded before the state was tracked
+    pub states: BTreeMap<String, Vec<Option<String>>>,
+    /// every channel added with `MoveVisPlugin::with_channel` by its name, one entry per sample,
+    /// `None` for samples recorded before the entity had the channel's component
+    pub channels: BTreeMap<String, Vec<Option<f32>>>,
+    /// whether each of `MoveVisConfig::triggers` and `MoveVisConfig::input_lanes` was held, by its
+    /// name, one entry per sample
+    pub inputs: BTreeMap<String, Vec<Option<bool>>>,
+    /// what the entity was touching, one entry per sample, only recorded by the rapier sources
+    pub contacts: Vec<Option<Contact>>,
+    /// gameplay events sent as [`MoveVisMarker`], in the order they happened
+    pub markers: Vec<Marker>,
+    /// previously completed recordings, the most recent first
+    pub ghosts: VecDeque<History>,
+    /// a pinned recording every later one is compared against
+    pub reference: Option<Box<History>>,
+    /// the config the recording was made under, captured along with its first sample
+    pub config: Option<MoveVisConfig>,
+    /// the game's own tuning when the recording started, see [`SettingsSnapshotPlugin`]
+    #[cfg(feature = "serde")]
+    pub settings: Option<ron::Value>,
+}
+
+impl History {
+    /// append a sample, deriving its acceleration and jerk from the previous one
+    pub fn push(&mut self, secs: f32, distance: Vec3, velocity: Vec3) {
+        let acceleration = self.derivative(
+            secs,
+            Some(velocity),
+            self.velocity.last().copied(),
+            self.acceleration.last().copied().flatten(),
+        );
+        let jerk = self.derivative(
+            secs,
+            acceleration,
+            self.acceleration.last().copied().flatten(),
+            self.jerk.last().copied().flatten(),
+        );
+
+        self.time.push(secs);
+        self.distance.push(distance);
+        self.velocity.push(velocity);
+        self.acceleration.push(acceleration);
+        self.jerk.push(jerk);
+    }
+
+    /// rate of change between `last_value` at the last sample and `value` at `secs`,
+    /// `None` until there are two values to difference
+    fn derivative(
+        &self,
+        secs: f32,
+        value: Option<Vec3>,
+        last_value: Option<Vec3>,
+        last_derived: Option<Vec3>,
+    ) -> Option<Vec3> {
+        match (self.time.last(), last_value) {
+            (Some(&last_secs), Some(last_value)) if secs > last_secs => {
+                Some((value? - last_value) / (secs - last_secs))
+            }
+            // no time has passed, keep the last rate of change rather than dividing by zero
+            _ => last_derived,
+        }
+    }
+
+    /// move the current recording to the front of `ghosts`, keeping at most `keep` of them
+    pub fn archive(&mut self, keep: usize) {
+        let mut ghosts = std::mem::take(&mut self.ghosts);
+        let reference = self.reference.take();
+        let run = std::mem::take(self);
+
+        if !run.time.is_empty() {
+            ghosts.push_front(run);
+        }
+        ghosts.truncate(keep);
+
+        self.ghosts = ghosts;
+        self.reference = reference;
+    }
+
+    /// a copy of the current recording, without its ghosts or reference
+    pub fn run(&self) -> History {
+        History {
+            ghosts: VecDeque::new(),
+            reference: None,
+            ..self.clone()
+        }
+    }
+
+    /// pin the current recording as the reference of every later one
+    pub fn pin_reference(&mut self) {
+        self.reference = Some(Box::new(self.run()));
+    }
+
+    /// `values` at `secs`, linearly interpolated between the two nearest samples.
+    /// `None` outside of the recording.
+    pub fn value_at(&self, values: &[Vec3], secs: f32) -> Option<Vec3> {
+        let i = self.time.partition_point(|&t| t < secs);
+
+        match (i.checked_sub(1), self.time.get(i)) {
+            // exactly on a sample, or on the first one
+            (_, Some(&t)) if t == secs => values.get(i).copied(),
+            (Some(prev), Some(&t)) => {
+                let prev_t = self.time[prev];
+                let s = (secs - prev_t) / (t - prev_t);
+
+                Some(values.get(prev)?.lerp(*values.get(i)?, s))
+            }
+            _ => None,
+        }
+    }
+
+    /// `values` minus the reference's at the same time since the start of the recording.
+    /// With `from_start`, each recording is measured from its own first sample,
+    /// so that runs starting at different places can still be compared.
+    pub fn difference(
+        &self,
+        reference: &History,
+        values: fn(&History) -> &[Vec3],
+        from_start: bool,
+    ) -> Vec<(f32, Vec3)> {
+        let origin = |history: &History| {
+            values(history)
+                .first()
+                .filter(|_| from_start)
+                .copied()
+                .unwrap_or_default()
+        };
+        let (origin, reference_origin) = (origin(self), origin(reference));
+
+        self.time
+            .iter()
+            .zip(values(self))
+            .filter_map(|(&t, &v)| {
+                let reference_value = reference.value_at(values(reference), t)?;
+
+                Some((t, (v - origin) - (reference_value - reference_origin)))
+            })
+            .collect()
+    }
+
+    /// the highest speed of the current recording, 0 before anything is recorded
+    pub fn max_speed(&self) -> f32 {
+        self.velocity
+            .iter()
+            .map(|velocity| velocity.length())
+            .fold(0.0, f32::max)
+    }
+
+    /// clear the current recording, `ghosts` are kept
+    pub fn clear(&mut self) {
+        self.time.clear();
+        self.distance.clear();
+        self.velocity.clear();
+        self.acceleration.clear();
+        self.jerk.clear();
+        self.states.clear();
+        self.channels.clear();
+        self.inputs.clear();
+        self.contacts.clear();
+        self.markers.clear();
+    }
+
+    /// drop every sample recorded before `secs`
+    pub fn truncate_before(&mut self, secs: f32) {
+        let len = self.time.partition_point(|&t| t < secs);
+
+        self.time.drain(..len);
+        self.distance.drain(..len);
+        self.velocity.drain(..len);
+        self.acceleration.drain(..len);
+        self.jerk.drain(..len);
+        truncate_lanes(&mut self.states, len);
+        truncate_lanes(&mut self.channels, len);
+        truncate_lanes(&mut self.inputs, len);
+        self.contacts.drain(..len.min(self.contacts.len()));
+        self.markers.retain(|marker| marker.time >= secs);
+    }
+
+    /// Drop the samples recorded before `secs`, but only once they make up an eighth of the
+    /// recording. Dropping samples moves all the others, doing it in batches keeps the cost per
+    /// sample the same however long the recording, at the price of keeping a little more of it.
+    pub(crate) fn truncate_overdue(&mut self, secs: f32) {
+        let overdue = self.time.partition_point(|&t| t < secs);
+
+        if overdue > 0 && overdue * 8 >= self.time.len() {
+            self.truncate_before(secs);
+        }
+    }
+}
+
+/// the hue of `speed` as a fraction of the colour wheel, blue when still, through green, to red at
+/// `max_speed`
+#[cfg(any(feature = "egui", feature = "sprites"))]
+pub(crate) fn speed_hue(speed: f32, max_speed: f32) -> f32 {
+    let s = if max_speed > 0.0 {
+        speed / max_speed
+    } else {
+        0.0
+    };
+
+    2.0 / 3.0 * (1.0 - s.clamp(0.0, 1.0))
+}
+
+/// drop the first `len` samples of every lane
+fn truncate_lanes<T>(lanes: &mut BTreeMap<String, Vec<Option<T>>>, len: usize) {
+    for lane in lanes.values_mut() {
+        lane.drain(..len.min(lane.len()));
+    }
+}
+
+/// Record `value` for the latest of `samples` samples, padding the samples recorded before the
+/// lane existed with `None`
+pub(crate) fn push_lane<T: Clone>(lane: &mut Vec<Option<T>>, samples: usize, value: T) {
+    if lane.len() + 1 < samples {
+        lane.resize(samples - 1, None);
+    }
+
+    if lane.len() < samples {
+        lane.push(Some(value));
+    }
+}
+
+/// A [`MoveVisMarker`] stamped onto a [`History`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Marker {
+    /// the time of the sample recorded when the marker was sent
+    pub time: f32,
+    pub label: String,
+    /// only with the `egui` or `sprites` features, the only ones that draw it
+    #[cfg(any(feature = "egui", feature = "sprites"))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color: Color,
 }
 
 #[derive(Component, Debug)]
 pub struct HistoryTimer(pub Timer);
 
-pub struct MoveVisPlugin;
+/// Drives the recording of a tracked entity from gameplay code or the UI.
+/// Every kind is applied in the order it was sent, a `Stop` sent after a `Start` sticks.
+#[derive(Clone, Debug)]
+pub enum ControlRecording {
+    /// Starts recording the [`History`], or resumes it if it was stopped. A finished recording, or
+    /// a stopped one in [`RecordMode::Rolling`], is moved to the ghosts and recorded afresh, so
+    /// that a frozen window is never joined up with what came after it.
+    Start { entity: Entity },
+    /// Pauses the recording, keeping what has been recorded so far. The triggers do not resume
+    /// it, only another `Start` does.
+    Stop { entity: Entity },
+    /// Throws away the [`History`], ghosts included, and waits for the next `Start` or trigger.
+    /// A pinned reference is kept, later recordings are still compared against it until it is
+    /// unpinned.
+    Clear { entity: Entity },
+}
+
+impl ControlRecording {
+    pub fn entity(&self) -> Entity {
+        match *self {
+            Self::Start { entity } | Self::Stop { entity } | Self::Clear { entity } => entity,
+        }
+    }
+}
+
+/// Marks a gameplay event, e.g. "jump" or "dash start", on the current sample of a tracked entity.
+/// Drawn as a labelled vertical line on the distance and velocity plots.
+#[derive(Clone, Debug)]
+pub struct MoveVisMarker {
+    pub entity: Entity,
+    pub label: String,
+    /// only with the `egui` or `sprites` features, see [`Marker::color`]
+    #[cfg(any(feature = "egui", feature = "sprites"))]
+    pub color: Color,
+}
+
+/// Records the [`History`] of every entity with [`TrackMovement`], without any UI.
+/// Add `MoveVisUiPlugin` as well to plot it.
+///
+/// Generic over the [`MovementSource`] the velocity is read from,
+/// e.g. `MoveVisPlugin::<TransformSource>::default()`
+pub struct MoveVisPlugin<S: MovementSource = DefaultMovementSource> {
+    channels: Vec<ChannelRegistration>,
+    source: PhantomData<S>,
+}
 
-impl Plugin for MoveVisPlugin {
+impl<S: MovementSource> Default for MoveVisPlugin<S> {
+    fn default() -> Self {
+        Self {
+            channels: Vec::new(),
+            source: PhantomData,
+        }
+    }
+}
+
+impl<S: MovementSource> MoveVisPlugin<S> {
+    /// Record `read` of the component `C` of every tracked entity along with each sample,
+    /// e.g. `.with_channel::<GravityScale>("Gravity Scale", |gravity_scale| gravity_scale.0)`.
+    /// Each channel is plotted on its own.
+    pub fn with_channel<C: Component>(mut self, name: &str, read: fn(&C) -> f32) -> Self {
+        self.channels.push(channel_registration(name, read));
+        self
+    }
+}
+
+impl<S: MovementSource> Plugin for MoveVisPlugin<S> {
     fn build(&self, app: &mut App) {
+        S::build(app);
+
+        #[cfg(feature = "serde")]
+        app.init_resource::<SettingsSnapshot>();
+
         app.init_resource::<MoveVisConfig>()
+            .add_event::<ControlRecording>()
+            .add_event::<MoveVisMarker>()
             .add_system(setup)
-            .add_system(record_history)
-            .add_system(plot);
+            .add_system(switch_mode.before(control_recording))
+            .add_system(trigger_recording.before(control_recording))
+            .add_system(control_recording.before(record_history))
+            .add_system(record_history.after(SampleVelocity));
+
+        for channel in &self.channels {
+            channel(app);
+        }
     }
 }
 
@@ -56,140 +472,481 @@ fn setup(
 ) {
     for entity in query.iter() {
         let mut timer = Timer::new(move_vis_config.track_duration, TimerMode::Once);
-        timer.pause();
+
+        if move_vis_config.mode == RecordMode::OneShot {
+            timer.pause();
+        }
 
         cmd.entity(entity)
             .insert(History::default())
-            .insert(HistoryTimer(timer));
+            .insert(HistoryTimer(timer))
+            .insert(SampledVelocity::default());
     }
 }
 
-fn plot_distance(ui: &mut egui::Ui, history: &History) {
-    let horizontal_distance = Line::new(
-        history
-            .distance
-            .iter()
-            .enumerate()
-            .map(|(x, &v)| [x as f64, v.x as f64])
-            .collect::<PlotPoints>(),
-    )
-    .color(egui::Color32::from_rgb(235, 171, 52))
-    .name("Horizonal Distance");
-
-    let vertical_distance = Line::new(
-        history
-            .distance
-            .iter()
-            .enumerate()
-            .map(|(x, &v)| [x as f64, v.y as f64])
-            .collect::<PlotPoints>(),
-    )
-    .color(egui::Color32::from_rgb(235, 64, 52))
-    .name("Vertical Distance");
-
-    Plot::new("Distance")
-        .legend(Legend::default())
-        .view_aspect(2.0)
-        .show(ui, |plot_ui| {
-            plot_ui.line(horizontal_distance);
-            plot_ui.line(vertical_distance);
-        });
-}
-
-fn plot_velocity(ui: &mut egui::Ui, history: &History) {
-    let horinzontal_velocity = Line::new(
-        history
-            .velocity
-            .iter()
-            .enumerate()
-            .map(|(x, &v)| [x as f64, v.x as f64])
-            .collect::<PlotPoints>(),
-    )
-    .color(egui::Color32::from_rgb(100, 200, 100))
-    .name("Horizontal Velocity");
-
-    let vertical_velocity = Line::new(
-        history
-            .velocity
+/// Starts afresh whenever `MoveVisConfig::mode` is switched, from the UI or from code.
+fn switch_mode(
+    move_vis_config: Res<MoveVisConfig>,
+    mut last_mode: Local<Option<RecordMode>>,
+    query: Query<Entity, With<TrackMovement>>,
+    mut control_recording: EventWriter<ControlRecording>,
+) {
+    let mode = move_vis_config.mode;
+
+    if last_mode
+        .replace(mode)
+        .is_none_or(|last_mode| last_mode == mode)
+    {
+        return;
+    }
+
+    control_recording.send_batch(
+        query
             .iter()
-            .enumerate()
-            .map(|(x, &v)| [x as f64, v.y as f64])
-            .collect::<PlotPoints>(),
-    )
-    .color(egui::Color32::from_rgb(100, 150, 250))
-    .name("Vertical Velocity");
+            .map(|entity| ControlRecording::Clear { entity }),
+    );
 
-    Plot::new("Velocity")
-        .legend(Legend::default())
-        .view_aspect(2.0)
-        .show(ui, |plot_ui| {
-            plot_ui.line(horinzontal_velocity);
-            plot_ui.line(vertical_velocity);
-        });
+    if mode == RecordMode::Rolling {
+        control_recording.send_batch(
+            query
+                .iter()
+                .map(|entity| ControlRecording::Start { entity }),
+        );
+    }
 }
 
-pub fn make_slider<'a, T: egui::emath::Numeric>(
-    caption: &'a str,
-    property: &'a mut T,
-    range: RangeInclusive<T>,
-) -> egui::Slider<'a> {
-    egui::Slider::new(property, range).text(caption)
-}
+fn trigger_recording(
+    move_vis_config: Res<MoveVisConfig>,
+    trigger_input: TriggerInput,
+    query: Query<(Entity, &History, &HistoryTimer), With<TrackMovement>>,
+    mut control_recording: EventWriter<ControlRecording>,
+) {
+    // a rolling recording never stops by itself, a trigger would only undo a freeze
+    if move_vis_config.mode == RecordMode::Rolling {
+        return;
+    }
 
-fn plot_ui(ui: &mut egui::Ui, mut move_vis_config: ResMut<MoveVisConfig>) {
-    let mut secs = move_vis_config.track_duration.as_secs();
+    if !trigger_input.any_pressed(&move_vis_config.triggers) {
+        return;
+    }
+
+    // only an idle recording is started, one stopped on purpose stays stopped while a trigger is held
+    let idle = |history: &History, history_timer: &HistoryTimer| {
+        let timer = &history_timer.0;
 
-    ui.add(make_slider("Duration(secs)", &mut secs, 1..=20));
+        timer.finished() || (timer.paused() && history.time.is_empty())
+    };
 
-    move_vis_config.track_duration = Duration::from_secs(secs);
+    control_recording.send_batch(
+        query
+            .iter()
+            .filter(|(_, history, history_timer)| idle(history, history_timer))
+            .map(|(entity, ..)| ControlRecording::Start { entity }),
+    );
 }
 
-fn plot(
-    move_vis_config: ResMut<MoveVisConfig>,
-    mut egui_context: ResMut<EguiContext>,
-    query: Query<&History, With<TrackMovement>>,
+fn control_recording(
+    move_vis_config: Res<MoveVisConfig>,
+    mut control_recording: EventReader<ControlRecording>,
+    mut query: Query<(&mut History, &mut HistoryTimer), With<TrackMovement>>,
 ) {
-    egui::Window::new("Movement").show(egui_context.ctx_mut(), |ui| {
-        plot_ui(ui, move_vis_config);
+    for control in control_recording.iter() {
+        let Ok((mut history, mut history_timer)) = query.get_mut(control.entity()) else {
+            continue;
+        };
+
+        match control {
+            ControlRecording::Clear { .. } => {
+                history.clear();
+                history.ghosts.clear();
+                history_timer.0.reset();
+                history_timer.0.pause();
+            }
+            ControlRecording::Stop { .. } => history_timer.0.pause(),
+            ControlRecording::Start { .. } => {
+                // time went on while frozen, carrying on would draw the samples either side as
+                // adjacent
+                let resumed =
+                    history_timer.0.paused() && move_vis_config.mode == RecordMode::Rolling;
 
-        for history in query.iter() {
-            plot_distance(ui, history);
+                if history_timer.0.paused() {
+                    history_timer.0.unpause();
+                }
 
-            plot_velocity(ui, history);
+                if history_timer.0.finished() || resumed {
+                    history.archive(move_vis_config.ghosts);
+                    history_timer.0.reset();
+                }
+            }
         }
-    });
+    }
 }
 
-fn record_history(
+pub(crate) fn record_history(
     time: Res<Time>,
-    input: Res<Input<KeyCode>>,
-    mut query: Query<(&Velocity, &Transform, &mut History, &mut HistoryTimer), With<TrackMovement>>,
+    move_vis_config: Res<MoveVisConfig>,
+    mut markers: EventReader<MoveVisMarker>,
+    trigger_input: TriggerInput,
+    #[cfg(feature = "serde")] settings_snapshot: Res<SettingsSnapshot>,
+    mut query: Query<
+        (
+            Entity,
+            &SampledVelocity,
+            &Transform,
+            &mut History,
+            &mut HistoryTimer,
+        ),
+        With<TrackMovement>,
+    >,
 ) {
-    for (velocity, transform, mut history, mut history_timer) in query.iter_mut() {
-        if input.pressed(KeyCode::A)
-            || input.pressed(KeyCode::Left)
-            || input.pressed(KeyCode::D)
-            || input.pressed(KeyCode::Right)
-            || input.pressed(KeyCode::Space)
-        {
-            if history_timer.0.paused() {
-                history_timer.0.unpause();
-            }
+    // a rolling recording never finishes, only its window is limited
+    let duration = match move_vis_config.mode {
+        RecordMode::OneShot => move_vis_config.track_duration,
+        RecordMode::Rolling => Duration::MAX,
+    };
 
-            if history_timer.0.finished() {
-                history.distance.clear();
-                history.velocity.clear();
-                history_timer.0.reset();
-            }
+    let markers: Vec<_> = markers.iter().collect();
+
+    for (entity, velocity, transform, mut history, mut history_timer) in query.iter_mut() {
+        if history_timer.0.duration() != duration {
+            history_timer.0.set_duration(duration);
         }
 
         if !history_timer.0.finished() && !history_timer.0.paused() {
-            history.distance.push(transform.translation);
+            let secs = history_timer.0.elapsed_secs();
+
+            // a new recording, remember what it is recorded under so that ghosts keep theirs
+            if history.time.is_empty() {
+                history.config = Some(move_vis_config.clone());
+
+                #[cfg(feature = "serde")]
+                {
+                    history.settings = settings_snapshot.0.clone();
+                }
+            }
+
+            history.push(secs, transform.translation, velocity.0);
+
+            let samples = history.time.len();
+            for input in move_vis_config
+                .triggers
+                .iter()
+                .chain(&move_vis_config.input_lanes)
+            {
+                push_lane(
+                    history.inputs.entry(input.to_string()).or_default(),
+                    samples,
+                    trigger_input.pressed(input),
+                );
+            }
 
-            #[cfg(feature = "bevy_rapier")]
-            history.velocity.push(velocity.linvel.extend(0.0));
+            // markers sent while not recording are dropped, there is no sample to stamp them on
+            for marker in markers.iter().filter(|marker| marker.entity == entity) {
+                history.markers.push(Marker {
+                    time: secs,
+                    label: marker.label.clone(),
+                    #[cfg(any(feature = "egui", feature = "sprites"))]
+                    color: marker.color,
+                });
+            }
+
+            if move_vis_config.mode == RecordMode::Rolling {
+                history.truncate_overdue(secs - move_vis_config.track_duration.as_secs_f32());
+            }
         }
 
         history_timer.0.tick(time.delta());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(samples: &[(f32, Vec3)]) -> History {
+        let mut history = History::default();
+
+        for &(secs, velocity) in samples {
+            history.push(secs, velocity * secs, velocity);
+        }
+
+        history
+    }
+
+    #[test]
+    fn push_derives_acceleration_and_jerk() {
+        let history = history(&[
+            (0.0, Vec3::ZERO),
+            (0.5, Vec3::new(1.0, 0.0, 0.0)),
+            (1.0, Vec3::new(3.0, -1.0, 0.0)),
+        ]);
+
+        assert_eq!(
+            history.acceleration,
+            [
+                None,
+                Some(Vec3::new(2.0, 0.0, 0.0)),
+                Some(Vec3::new(4.0, -2.0, 0.0))
+            ]
+        );
+        assert_eq!(history.jerk, [None, None, Some(Vec3::new(4.0, -4.0, 0.0))]);
+    }
+
+    #[test]
+    fn push_without_time_passing_keeps_the_last_rate() {
+        let history = history(&[
+            (0.0, Vec3::ZERO),
+            (0.5, Vec3::new(1.0, 0.0, 0.0)),
+            (0.5, Vec3::new(5.0, 0.0, 0.0)),
+        ]);
+
+        assert_eq!(history.acceleration[2], history.acceleration[1]);
+        assert_eq!(history.jerk[2], history.jerk[1]);
+        assert!(history.acceleration.iter().flatten().all(|a| a.is_finite()));
+    }
+
+    #[test]
+    fn value_at_interpolates() {
+        let history = history(&[(0.0, Vec3::ZERO), (1.0, Vec3::new(2.0, 4.0, 0.0))]);
+
+        assert_eq!(
+            history.value_at(&history.velocity, 0.25),
+            Some(Vec3::new(0.5, 1.0, 0.0))
+        );
+        assert_eq!(history.value_at(&history.velocity, 0.0), Some(Vec3::ZERO));
+        assert_eq!(
+            history.value_at(&history.velocity, 1.0),
+            Some(Vec3::new(2.0, 4.0, 0.0))
+        );
+        assert_eq!(history.value_at(&history.velocity, -0.1), None);
+        assert_eq!(history.value_at(&history.velocity, 1.1), None);
+    }
+
+    #[test]
+    fn difference_against_reference() {
+        let reference = history(&[(0.0, Vec3::ZERO), (1.0, Vec3::new(2.0, 0.0, 0.0))]);
+        let run = history(&[(0.5, Vec3::new(2.0, 0.0, 0.0)), (1.5, Vec3::ZERO)]);
+
+        // the reference is 1 at 0.5s, and over by 1.5s
+        assert_eq!(
+            run.difference(&reference, |history| &history.velocity, false),
+            [(0.5, Vec3::new(1.0, 0.0, 0.0))]
+        );
+
+        // measured from each recording's own first sample instead
+        let run = history(&[
+            (0.5, Vec3::new(4.0, 0.0, 0.0)),
+            (1.0, Vec3::new(6.0, 0.0, 0.0)),
+        ]);
+        assert_eq!(
+            run.difference(&reference, |history| &history.velocity, true),
+            [
+                (0.5, Vec3::new(-1.0, 0.0, 0.0)),
+                (1.0, Vec3::new(0.0, 0.0, 0.0))
+            ]
+        );
+    }
+
+    #[test]
+    fn truncate_before_keeps_samples_aligned() {
+        let mut history = History::default();
+
+        for i in 0..4 {
+            let secs = i as f32;
+            history.push(secs, Vec3::X * secs, Vec3::Y * secs);
+        }
+
+        history.truncate_before(1.5);
+
+        assert_eq!(history.time, [2.0, 3.0]);
+        assert_eq!(history.distance, [Vec3::X * 2.0, Vec3::X * 3.0]);
+        assert_eq!(history.velocity, [Vec3::Y * 2.0, Vec3::Y * 3.0]);
+        assert_eq!(history.acceleration.len(), 2);
+        assert_eq!(history.jerk.len(), 2);
+    }
+
+    #[test]
+    fn push_lane_pads_late_lanes() {
+        let mut lane = Vec::new();
+
+        push_lane(&mut lane, 3, 1.0);
+        assert_eq!(lane, [None, None, Some(1.0)]);
+
+        // at most one value per sample
+        push_lane(&mut lane, 3, 2.0);
+        assert_eq!(lane, [None, None, Some(1.0)]);
+    }
+
+    #[test]
+    fn truncate_before_keeps_lanes_aligned() {
+        let mut history = History::default();
+
+        for i in 0..4 {
+            let secs = i as f32;
+            history.push(secs, Vec3::ZERO, Vec3::ZERO);
+
+            let samples = history.time.len();
+            push_lane(
+                history.channels.entry("All".into()).or_default(),
+                samples,
+                secs,
+            );
+            // started being recorded with the third sample
+            if i >= 2 {
+                push_lane(
+                    history.channels.entry("Late".into()).or_default(),
+                    samples,
+                    secs,
+                );
+            }
+        }
+
+        history.truncate_before(1.5);
+
+        assert_eq!(history.channels["All"], [Some(2.0), Some(3.0)]);
+        assert_eq!(history.channels["Late"], [Some(2.0), Some(3.0)]);
+    }
+
+    #[test]
+    fn truncate_before_drops_older_markers() {
+        let mut history = History::default();
+
+        for (secs, label) in [(0.0, "jump"), (2.0, "landed")] {
+            history.push(secs, Vec3::ZERO, Vec3::ZERO);
+            history.markers.push(Marker {
+                time: secs,
+                label: label.to_string(),
+                #[cfg(any(feature = "egui", feature = "sprites"))]
+                color: Color::WHITE,
+            });
+        }
+
+        history.truncate_before(1.0);
+
+        let labels: Vec<_> = history.markers.iter().map(|m| m.label.as_str()).collect();
+        assert_eq!(labels, ["landed"]);
+    }
+
+    #[test]
+    fn truncate_overdue_drops_samples_in_batches() {
+        let mut history = History::default();
+        for i in 0..16 {
+            history.push(i as f32, Vec3::ZERO, Vec3::ZERO);
+        }
+
+        history.truncate_overdue(1.0);
+        assert_eq!(history.time.len(), 16);
+
+        history.truncate_overdue(2.0);
+        assert_eq!(history.time.first(), Some(&2.0));
+        assert_eq!(history.time.len(), 14);
+    }
+
+    /// an app recording every frame of a single tracked entity, its velocity read from its transform
+    fn rolling_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(MoveVisConfig {
+                mode: RecordMode::Rolling,
+                track_duration: Duration::MAX,
+                ..default()
+            })
+            .add_plugin(MoveVisPlugin::<TransformSource>::default());
+
+        let entity = app.world.spawn((Transform::default(), TrackMovement)).id();
+
+        (app, entity)
+    }
+
+    #[test]
+    fn resuming_a_rolling_recording_starts_a_new_one() {
+        let (mut app, entity) = rolling_app();
+
+        for _ in 0..3 {
+            app.update();
+        }
+        app.world.send_event(ControlRecording::Stop { entity });
+        app.update();
+        let frozen = app.world.get::<History>(entity).unwrap().time.len();
+
+        app.world.send_event(ControlRecording::Start { entity });
+        app.update();
+
+        let history = app.world.get::<History>(entity).unwrap();
+        assert_eq!(history.ghosts.len(), 1);
+        assert_eq!(history.ghosts[0].time.len(), frozen);
+        assert_eq!(history.time, [0.0]);
+    }
+
+    fn one_shot_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(MoveVisConfig {
+                track_duration: Duration::MAX,
+                ..default()
+            })
+            .init_resource::<Input<KeyCode>>()
+            .add_plugin(MoveVisPlugin::<TransformSource>::default());
+
+        let entity = app.world.spawn((Transform::default(), TrackMovement)).id();
+
+        (app, entity)
+    }
+
+    fn recording(app: &App, entity: Entity) -> bool {
+        !app.world.get::<HistoryTimer>(entity).unwrap().0.paused()
+    }
+
+    #[test]
+    fn stop_sticks_while_a_trigger_is_held() {
+        let (mut app, entity) = one_shot_app();
+
+        app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::D);
+        app.update();
+        app.update();
+        assert!(recording(&app, entity));
+
+        app.world.send_event(ControlRecording::Stop { entity });
+        for _ in 0..3 {
+            app.update();
+        }
+        assert!(!recording(&app, entity));
+
+        app.world.send_event(ControlRecording::Start { entity });
+        app.update();
+        assert!(recording(&app, entity));
+    }
+
+    #[test]
+    fn controls_apply_in_the_order_they_were_sent() {
+        let (mut app, entity) = one_shot_app();
+
+        app.world.send_event(ControlRecording::Start { entity });
+        app.world.send_event(ControlRecording::Stop { entity });
+        app.update();
+        assert!(!recording(&app, entity));
+
+        app.world.send_event(ControlRecording::Stop { entity });
+        app.world.send_event(ControlRecording::Start { entity });
+        app.update();
+        assert!(recording(&app, entity));
+    }
+
+    #[test]
+    fn switching_the_mode_from_code_starts_afresh() {
+        let (mut app, entity) = one_shot_app();
+
+        app.update();
+        assert!(!recording(&app, entity));
+
+        app.world.resource_mut::<MoveVisConfig>().mode = RecordMode::Rolling;
+        app.update();
+        assert!(recording(&app, entity));
+        assert_eq!(app.world.get::<History>(entity).unwrap().time, [0.0]);
+
+        app.world.resource_mut::<MoveVisConfig>().mode = RecordMode::OneShot;
+        app.update();
+        assert!(!recording(&app, entity));
+        assert!(app.world.get::<History>(entity).unwrap().time.is_empty());
+    }
+}
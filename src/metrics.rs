@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use crate::History;
+
+/// Measurements of one jump found in a [`History`], from take-off to landing.
+///
+/// Heights and ranges are in the units of `Transform.translation`, `y` being up.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JumpMetrics {
+    /// seconds into the recording the jump started
+    pub take_off: f32,
+    /// highest point above the take-off position
+    pub apex_height: f32,
+    pub time_to_apex: f32,
+    /// seconds from take-off to landing
+    pub air_time: f32,
+    /// horizontal distance between the take-off and landing positions
+    pub horizontal_range: f32,
+    /// velocity of the last airborne sample
+    pub landing_velocity: Vec3,
+}
+
+impl History {
+    /// Every completed jump of the current recording.
+    ///
+    /// A jump takes off when the vertical speed rises above `airborne_speed` and lands once it
+    /// has fallen and settled back within `airborne_speed`. Walking off a ledge is not a jump.
+    pub fn jumps(&self, airborne_speed: f32) -> Vec<JumpMetrics> {
+        let mut jumps = Vec::new();
+        // index of the take-off sample, and whether the jump has started falling
+        let mut airborne: Option<(usize, bool)> = None;
+
+        for (i, velocity) in self.velocity.iter().enumerate() {
+            airborne = match airborne {
+                None if velocity.y > airborne_speed => Some((i, false)),
+                Some((take_off, _)) if velocity.y < -airborne_speed => Some((take_off, true)),
+                Some((take_off, true)) if velocity.y.abs() <= airborne_speed => {
+                    jumps.push(self.jump_metrics(take_off, i));
+                    None
+                }
+                airborne => airborne,
+            };
+        }
+
+        jumps
+    }
+
+    fn jump_metrics(&self, take_off: usize, landing: usize) -> JumpMetrics {
+        let start = self.distance[take_off];
+        let range = self.distance[landing] - start;
+
+        let (apex, apex_y) = self.distance[take_off..=landing].iter().enumerate().fold(
+            (0, start.y),
+            |(apex, apex_y), (i, distance)| {
+                if distance.y > apex_y {
+                    (i, distance.y)
+                } else {
+                    (apex, apex_y)
+                }
+            },
+        );
+
+        JumpMetrics {
+            take_off: self.time[take_off],
+            apex_height: apex_y - start.y,
+            time_to_apex: self.time[take_off + apex] - self.time[take_off],
+            air_time: self.time[landing] - self.time[take_off],
+            horizontal_range: Vec3::new(range.x, 0.0, range.z).length(),
+            landing_velocity: self.velocity[landing - 1],
+        }
+    }
+}
+
+/// The designed jump, from its height and the time it takes to reach it,
+/// drawn over the vertical distance of every detected jump
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct JumpArc {
+    pub jump_height: f32,
+    pub time_to_apex: f32,
+}
+
+impl JumpArc {
+    pub fn new(jump_height: f32, time_to_apex: f32) -> Self {
+        Self {
+            jump_height,
+            time_to_apex,
+        }
+    }
+
+    /// the downward acceleration needed to reach `jump_height` in `time_to_apex`
+    pub fn gravity(&self) -> f32 {
+        2.0 * self.jump_height / self.time_to_apex.powi(2)
+    }
+
+    pub fn take_off_speed(&self) -> f32 {
+        2.0 * self.jump_height / self.time_to_apex
+    }
+
+    /// height above the take-off position `secs` after take-off, back to 0 after `air_time`
+    pub fn height_at(&self, secs: f32) -> f32 {
+        self.take_off_speed() * secs - 0.5 * self.gravity() * secs.powi(2)
+    }
+
+    pub fn air_time(&self) -> f32 {
+        2.0 * self.time_to_apex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jump_arc_peaks_at_jump_height_and_lands_after_air_time() {
+        let jump_arc = JumpArc::new(4.0, 0.4);
+
+        assert!((jump_arc.height_at(jump_arc.time_to_apex) - jump_arc.jump_height).abs() < 1e-5);
+        assert!(jump_arc.height_at(jump_arc.air_time()).abs() < 1e-5);
+    }
+}
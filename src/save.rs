@@ -0,0 +1,356 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{export::file_path, History, MoveVisConfig};
+
+/// A [`History`] together with everything needed to tell how it was produced
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Recording {
+    pub name: String,
+    pub config: MoveVisConfig,
+    pub history: History,
+    /// the game's own tuning when the recording started, see [`SettingsSnapshotPlugin`]
+    pub settings: Option<ron::Value>,
+}
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Ron(ron::Error),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::Json(e) => e.fmt(f),
+            Self::Ron(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<io::Error> for RecordingError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for RecordingError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<ron::Error> for RecordingError {
+    fn from(e: ron::Error) -> Self {
+        Self::Ron(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for RecordingError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Self::Ron(e.code)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SaveFormat {
+    #[default]
+    Ron,
+    Json,
+}
+
+impl SaveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Ron => "ron",
+            Self::Json => "json",
+        }
+    }
+
+    /// guessed from the extension of `path`, anything but `.json` is read as RON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::Json,
+            _ => Self::Ron,
+        }
+    }
+}
+
+impl Recording {
+    /// the current recording of `history`, without its ghosts or reference, with the config and
+    /// settings it was recorded under moved out of it, falling back to `move_vis_config` for a
+    /// history that has not recorded anything yet
+    pub fn new(name: String, history: &History, move_vis_config: &MoveVisConfig) -> Self {
+        let mut history = history.run();
+
+        Self {
+            name,
+            config: history
+                .config
+                .take()
+                .unwrap_or_else(|| move_vis_config.clone()),
+            settings: history.settings.take(),
+            history,
+        }
+    }
+
+    pub fn to_string(&self, format: SaveFormat) -> Result<String, RecordingError> {
+        Ok(match format {
+            SaveFormat::Ron => ron::ser::to_string_pretty(self, default())?,
+            SaveFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+
+    pub fn from_str(s: &str, format: SaveFormat) -> Result<Self, RecordingError> {
+        Ok(match format {
+            // recordings saved before the first acceleration and jerks were left out have them
+            // without `Some`
+            SaveFormat::Ron => ron::Options::default()
+                .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+                .from_str(s)?,
+            SaveFormat::Json => serde_json::from_str(s)?,
+        })
+    }
+
+    /// write to `path`, as JSON if its extension is `.json` and RON otherwise
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordingError> {
+        let path = path.as_ref();
+
+        fs::write(path, self.to_string(SaveFormat::from_path(path))?)?;
+
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let path = path.as_ref();
+
+        Self::from_str(&fs::read_to_string(path)?, SaveFormat::from_path(path))
+    }
+}
+
+/// The latest snapshot of the game's tuning, saved along with every [`Recording`]
+#[derive(Debug, Default, Resource)]
+pub struct SettingsSnapshot(pub Option<ron::Value>);
+
+/// Keeps [`SettingsSnapshot`] up to date with the resource `R`,
+/// e.g. `SettingsSnapshotPlugin::<PlayerMovementSettings>::default()`
+pub struct SettingsSnapshotPlugin<R: Resource + Serialize>(PhantomData<R>);
+
+impl<R: Resource + Serialize> Default for SettingsSnapshotPlugin<R> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<R: Resource + Serialize> Plugin for SettingsSnapshotPlugin<R> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsSnapshot>()
+            .add_system(snapshot_settings::<R>);
+    }
+}
+
+fn snapshot_settings<R: Resource + Serialize>(
+    settings: Res<R>,
+    mut settings_snapshot: ResMut<SettingsSnapshot>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    // go through RON to turn any Serialize into a self-describing value
+    let snapshot = ron::to_string(&*settings)
+        .map_err(RecordingError::from)
+        .and_then(|s| Ok(ron::from_str(&s)?));
+
+    match snapshot {
+        Ok(snapshot) => settings_snapshot.0 = Some(snapshot),
+        Err(e) => error!("failed to snapshot settings: {e}"),
+    }
+}
+
+/// Save one [`Recording`] per `(entity, name, history)` into `MoveVisConfig::export_dir`, errors are logged.
+/// Only the current recording is saved, not the ghosts or reference, each with the config and
+/// settings it was recorded under, `move_vis_config` is only used for a history that has not
+/// recorded anything yet.
+pub fn save_recordings<'a>(
+    move_vis_config: &MoveVisConfig,
+    histories: impl Iterator<Item = (Entity, String, &'a History)>,
+) {
+    for (entity, name, history) in histories {
+        let path = file_path(
+            &move_vis_config.export_dir,
+            entity,
+            &name,
+            move_vis_config.save_format.extension(),
+        );
+
+        let recording = Recording::new(name, history, move_vis_config);
+
+        match recording.save(&path) {
+            Ok(()) => info!("saved {}", path.display()),
+            Err(e) => error!("failed to save {}: {e}", path.display()),
+        }
+    }
+}
+
+/// A [`Recording`] loaded back from disk, shown next to the live entities but never recorded into
+#[derive(Component, Debug)]
+pub struct LoadedRecording {
+    pub path: PathBuf,
+    pub config: MoveVisConfig,
+    pub settings: Option<ron::Value>,
+}
+
+/// Spawn a detached entity holding the [`History`] of the recording saved at `path`
+pub fn load_recording(
+    commands: &mut Commands,
+    path: impl AsRef<Path>,
+) -> Result<Entity, RecordingError> {
+    let path = path.as_ref();
+    let recording = Recording::load(path)?;
+    let mut history = recording.history;
+    history.config = Some(recording.config.clone());
+    history.settings = recording.settings.clone();
+
+    Ok(commands
+        .spawn((
+            Name::new(format!("{} ({})", recording.name, path.display())),
+            history,
+            LoadedRecording {
+                path: path.to_path_buf(),
+                config: recording.config,
+                settings: recording.settings,
+            },
+        ))
+        .id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // saved before input lanes, contacts, markers and the world drawing settings existed,
+    // and while every sample had an acceleration
+    const OLD_RON: &str = r#"(
+        name: "Player",
+        config: (
+            mode: Rolling,
+            track_duration: (secs: 3, nanos: 0),
+            plot_z: false,
+        ),
+        history: (
+            time: [0.0, 0.5],
+            velocity: [(0.0, 0.0, 0.0), (2.0, 0.0, 0.0)],
+            distance: [(0.0, 0.0, 0.0), (1.0, 0.0, 0.0)],
+            acceleration: [(0.0, 0.0, 0.0), (4.0, 0.0, 0.0)],
+        ),
+    )"#;
+
+    const OLD_JSON: &str = r#"{
+        "name": "Player",
+        "config": {
+            "mode": "Rolling",
+            "track_duration": { "secs": 3, "nanos": 0 },
+            "plot_z": false
+        },
+        "history": {
+            "time": [0.0, 0.5],
+            "velocity": [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0]],
+            "distance": [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]],
+            "acceleration": [[0.0, 0.0, 0.0], [4.0, 0.0, 0.0]]
+        }
+    }"#;
+
+    fn assert_old_recording(recording: &Recording) {
+        assert_eq!(recording.name, "Player");
+        assert_eq!(recording.config.mode, crate::RecordMode::Rolling);
+        assert_eq!(recording.config.track_duration.as_secs(), 3);
+        // missing fields fall back to their defaults
+        assert_eq!(recording.config.ghosts, MoveVisConfig::default().ghosts);
+        assert_eq!(recording.config.jump_arc, None);
+        assert_eq!(recording.history.time, [0.0, 0.5]);
+        assert_eq!(recording.history.velocity[1], Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(
+            recording.history.acceleration[1],
+            Some(Vec3::new(4.0, 0.0, 0.0))
+        );
+        assert!(recording.history.jerk.is_empty());
+        assert!(recording.history.contacts.is_empty());
+        assert!(recording.history.markers.is_empty());
+        assert!(recording.settings.is_none());
+    }
+
+    fn assert_round_trip(recording: &Recording, format: SaveFormat) {
+        let reloaded = Recording::from_str(&recording.to_string(format).unwrap(), format).unwrap();
+
+        assert_eq!(reloaded.name, recording.name);
+        assert_eq!(reloaded.config.mode, recording.config.mode);
+        assert_eq!(reloaded.history.time, recording.history.time);
+        assert_eq!(reloaded.history.distance, recording.history.distance);
+        assert_eq!(reloaded.history.velocity, recording.history.velocity);
+        assert_eq!(
+            reloaded.history.acceleration,
+            recording.history.acceleration
+        );
+    }
+
+    #[test]
+    fn saves_the_config_recorded_under() {
+        let recorded_under = MoveVisConfig {
+            ghosts: 7,
+            ..default()
+        };
+        let history = History {
+            time: vec![0.0],
+            config: Some(recorded_under),
+            ..default()
+        };
+
+        let recording = Recording::new("Player".to_string(), &history, &MoveVisConfig::default());
+
+        assert_eq!(recording.config.ghosts, 7);
+        // saved once, not again inside the history
+        assert!(recording.history.config.is_none());
+    }
+
+    #[test]
+    fn saves_the_current_recording_only() {
+        let mut history = History::default();
+        history.push(0.0, Vec3::ZERO, Vec3::ZERO);
+        history.pin_reference();
+        history.archive(1);
+        history.push(0.0, Vec3::X, Vec3::X);
+
+        let recording = Recording::new("Player".to_string(), &history, &MoveVisConfig::default());
+
+        assert_eq!(recording.history.distance, [Vec3::X]);
+        assert!(recording.history.ghosts.is_empty());
+        assert!(recording.history.reference.is_none());
+    }
+
+    #[test]
+    fn loads_old_ron() {
+        let recording = Recording::from_str(OLD_RON, SaveFormat::Ron).unwrap();
+
+        assert_old_recording(&recording);
+        assert_round_trip(&recording, SaveFormat::Ron);
+    }
+
+    #[test]
+    fn loads_old_json() {
+        let recording = Recording::from_str(OLD_JSON, SaveFormat::Json).unwrap();
+
+        assert_old_recording(&recording);
+        assert_round_trip(&recording, SaveFormat::Json);
+    }
+}
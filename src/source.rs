@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+
+#[cfg(feature = "bevy_rapier")]
+use bevy_rapier2d::prelude::{RapierContext, Velocity};
+
+#[cfg(feature = "bevy_rapier3d")]
+use bevy_rapier3d::prelude::{RapierContext as RapierContext3d, Velocity as Velocity3d};
+
+use crate::TrackMovement;
+#[cfg(any(feature = "bevy_rapier", feature = "bevy_rapier3d"))]
+use crate::{push_lane, record_history, Contact, History};
+
+/// The velocity of a tracked entity for the current frame, as reported by its [`MovementSource`]
+#[derive(Clone, Component, Debug, Default)]
+pub struct SampledVelocity(pub Vec3);
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemLabel)]
+pub struct SampleVelocity;
+
+/// Where the velocity of a tracked entity comes from.
+///
+/// An implementation registers the systems that keep [`SampledVelocity`] up to date.
+/// Those systems should be labelled with [`SampleVelocity`] so that they run before the
+/// history is recorded.
+pub trait MovementSource: Send + Sync + 'static {
+    fn build(app: &mut App);
+}
+
+/// Reads the linear velocity from `bevy_rapier2d`'s [`Velocity`], and records every
+/// [`Contact`] from its `RapierContext`
+#[cfg(feature = "bevy_rapier")]
+pub struct RapierSource;
+
+#[cfg(feature = "bevy_rapier")]
+impl MovementSource for RapierSource {
+    fn build(app: &mut App) {
+        app.add_system(sample_rapier_velocity.label(SampleVelocity))
+            .add_system(record_rapier_contacts.after(record_history));
+    }
+}
+
+#[cfg(feature = "bevy_rapier")]
+fn sample_rapier_velocity(
+    mut query: Query<(&Velocity, &mut SampledVelocity), With<TrackMovement>>,
+) {
+    for (velocity, mut sampled_velocity) in query.iter_mut() {
+        sampled_velocity.0 = velocity.linvel.extend(0.0);
+    }
+}
+
+/// the number of colliders touched and the most upward normal, like the example's
+/// `get_standing_normal`, from the normals of every touching collider, each pointing away from it
+#[cfg(any(feature = "bevy_rapier", feature = "bevy_rapier3d"))]
+fn push_contact(history: &mut History, touching: impl Iterator<Item = Vec<Vec3>>) {
+    let mut contact = Contact::default();
+
+    for normals in touching {
+        contact.count += 1;
+
+        for normal in normals {
+            if contact.normal.is_none_or(|best| normal.y > best.y) {
+                contact.normal = Some(normal);
+            }
+        }
+    }
+
+    let samples = history.time.len();
+    push_lane(&mut history.contacts, samples, contact);
+}
+
+#[cfg(feature = "bevy_rapier")]
+fn record_rapier_contacts(
+    rapier_context: Res<RapierContext>,
+    mut query: Query<(Entity, &mut History), With<TrackMovement>>,
+) {
+    for (entity, mut history) in query.iter_mut() {
+        let touching = rapier_context
+            .contacts_with(entity)
+            .filter(|pair| pair.has_any_active_contacts())
+            .map(|pair| {
+                // the normal points from the first collider to the second
+                let sign = if pair.collider1() == entity {
+                    -1.0
+                } else {
+                    1.0
+                };
+
+                pair.manifolds()
+                    .map(|manifold| (sign * manifold.normal()).extend(0.0))
+                    .collect()
+            });
+
+        push_contact(&mut history, touching);
+    }
+}
+
+/// Reads the linear velocity from `bevy_rapier3d`'s `Velocity`, and records every [`Contact`]
+/// from its `RapierContext`
+#[cfg(feature = "bevy_rapier3d")]
+pub struct Rapier3dSource;
+
+#[cfg(feature = "bevy_rapier3d")]
+impl MovementSource for Rapier3dSource {
+    fn build(app: &mut App) {
+        app.add_system(sample_rapier3d_velocity.label(SampleVelocity))
+            .add_system(record_rapier3d_contacts.after(record_history));
+    }
+}
+
+#[cfg(feature = "bevy_rapier3d")]
+fn sample_rapier3d_velocity(
+    mut query: Query<(&Velocity3d, &mut SampledVelocity), With<TrackMovement>>,
+) {
+    for (velocity, mut sampled_velocity) in query.iter_mut() {
+        sampled_velocity.0 = velocity.linvel;
+    }
+}
+
+#[cfg(feature = "bevy_rapier3d")]
+fn record_rapier3d_contacts(
+    rapier_context: Res<RapierContext3d>,
+    mut query: Query<(Entity, &mut History), With<TrackMovement>>,
+) {
+    for (entity, mut history) in query.iter_mut() {
+        let touching = rapier_context
+            .contacts_with(entity)
+            .filter(|pair| pair.has_any_active_contacts())
+            .map(|pair| {
+                let sign = if pair.collider1() == entity {
+                    -1.0
+                } else {
+                    1.0
+                };
+
+                pair.manifolds()
+                    .map(|manifold| sign * manifold.normal())
+                    .collect()
+            });
+
+        push_contact(&mut history, touching);
+    }
+}
+
+/// Derives the velocity from successive `Transform.translation` values,
+/// for kinematic controllers or physics engines without a rapier `Velocity`
+pub struct TransformSource;
+
+impl MovementSource for TransformSource {
+    fn build(app: &mut App) {
+        app.add_system(setup_transform_source)
+            .add_system(sample_transform_velocity.label(SampleVelocity));
+    }
+}
+
+#[derive(Component, Debug, Default)]
+struct LastTranslation(Option<Vec3>);
+
+fn setup_transform_source(mut cmd: Commands, query: Query<Entity, Added<TrackMovement>>) {
+    for entity in query.iter() {
+        cmd.entity(entity).insert(LastTranslation::default());
+    }
+}
+
+fn sample_transform_velocity(
+    time: Res<Time>,
+    mut query: Query<(&Transform, &mut LastTranslation, &mut SampledVelocity), With<TrackMovement>>,
+) {
+    let delta = time.delta_seconds();
+
+    for (transform, mut last_translation, mut sampled_velocity) in query.iter_mut() {
+        // the first frame has nothing to difference against
+        if let Some(last) = last_translation.0 {
+            if delta > 0.0 {
+                sampled_velocity.0 = (transform.translation - last) / delta;
+            }
+        }
+
+        last_translation.0 = Some(transform.translation);
+    }
+}
+
+#[cfg(feature = "bevy_rapier")]
+pub type DefaultMovementSource = RapierSource;
+
+#[cfg(all(feature = "bevy_rapier3d", not(feature = "bevy_rapier")))]
+pub type DefaultMovementSource = Rapier3dSource;
+
+#[cfg(not(any(feature = "bevy_rapier", feature = "bevy_rapier3d")))]
+pub type DefaultMovementSource = TransformSource;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    #[test]
+    fn transform_source_differences_translations() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        TransformSource::build(&mut app);
+
+        let entity = app
+            .world
+            .spawn((
+                Transform::default(),
+                TrackMovement,
+                SampledVelocity::default(),
+            ))
+            .id();
+        let startup = app.world.resource::<Time>().startup();
+
+        for frame in 0..5 {
+            app.world
+                .get_mut::<Transform>(entity)
+                .unwrap()
+                .translation
+                .x = frame as f32;
+            app.insert_resource(TimeUpdateStrategy::ManualInstant(
+                startup + Duration::from_secs_f32(0.1 * frame as f32),
+            ));
+            app.update();
+        }
+
+        let velocity = app.world.get::<SampledVelocity>(entity).unwrap().0;
+
+        assert!((velocity.x - 10.0).abs() < 1e-3, "{velocity}");
+        assert_eq!(velocity.y, 0.0);
+    }
+}
@@ -0,0 +1,53 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::{push_lane, record_history, History, TrackMovement};
+
+/// A state of a tracked entity recorded along with every sample, e.g. the jump state of a
+/// player controller. Needs [`TrackStatePlugin`] for the same `S`.
+#[derive(Clone, Component, Debug, Default)]
+pub struct TrackState<S: Debug + Eq + Send + Sync + 'static>(pub S);
+
+/// Records [`TrackState<S>`] into `History::states`, drawn as a band under the plots
+pub struct TrackStatePlugin<S>(PhantomData<S>);
+
+impl<S> Default for TrackStatePlugin<S> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S: Debug + Eq + Send + Sync + 'static> Plugin for TrackStatePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_system(record_state::<S>.after(record_history));
+    }
+}
+
+/// the key of the lane `S` is recorded into, its full type name so that states named alike in
+/// different modules, or differing only in their generic arguments, get a lane each
+fn lane_key<S>() -> &'static str {
+    std::any::type_name::<S>()
+}
+
+impl History {
+    /// the lane [`TrackState<S>`] was recorded into, one entry per sample
+    pub fn state_lane<S: 'static>(&self) -> Option<&[Option<String>]> {
+        self.states.get(lane_key::<S>()).map(Vec::as_slice)
+    }
+}
+
+fn record_state<S: Debug + Eq + Send + Sync + 'static>(
+    mut query: Query<(&TrackState<S>, &mut History), With<TrackMovement>>,
+) {
+    for (state, mut history) in query.iter_mut() {
+        let History { time, states, .. } = &mut *history;
+
+        push_lane(
+            states.entry(lane_key::<S>().to_string()).or_default(),
+            time.len(),
+            format!("{:?}", state.0),
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+/// An input which starts a recording while it is held
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum RecordTrigger {
+    Key(KeyCode),
+    MouseButton(MouseButton),
+    /// matches the button on any connected gamepad
+    GamepadButton(GamepadButtonType),
+    /// a positive threshold fires when the axis is at or above it, a negative one at or below it,
+    /// its magnitude is kept to `0.1..=1.0`
+    GamepadAxis {
+        axis: GamepadAxisType,
+        threshold: f32,
+    },
+}
+
+impl RecordTrigger {
+    /// the triggers used by the example, A/D/Left/Right to run and Space to jump
+    pub fn defaults() -> Vec<Self> {
+        [
+            KeyCode::A,
+            KeyCode::D,
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::Space,
+        ]
+        .into_iter()
+        .map(Self::Key)
+        .collect()
+    }
+}
+
+impl fmt::Display for RecordTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Key(key) => write!(f, "Key {key:?}"),
+            Self::MouseButton(button) => write!(f, "Mouse {button:?}"),
+            Self::GamepadButton(button) => write!(f, "Gamepad {button:?}"),
+            Self::GamepadAxis { axis, threshold } if *threshold < 0.0 => {
+                write!(f, "Gamepad {axis:?} <= {threshold:.2}")
+            }
+            Self::GamepadAxis { axis, threshold } => {
+                write!(f, "Gamepad {axis:?} >= {threshold:.2}")
+            }
+        }
+    }
+}
+
+const GAMEPAD_AXES: [GamepadAxisType; 6] = [
+    GamepadAxisType::LeftStickX,
+    GamepadAxisType::LeftStickY,
+    GamepadAxisType::LeftZ,
+    GamepadAxisType::RightStickX,
+    GamepadAxisType::RightStickY,
+    GamepadAxisType::RightZ,
+];
+
+// how far an axis has to be pushed to be picked up when binding a new trigger
+const BIND_AXIS_THRESHOLD: f32 = 0.5;
+
+// a smaller threshold would be reached by a stick at rest and fire every frame
+pub(crate) const MIN_AXIS_THRESHOLD: f32 = 0.1;
+
+/// whether an axis at `value` is past `threshold`, see [`RecordTrigger::GamepadAxis`]
+fn past_threshold(value: f32, threshold: f32) -> bool {
+    let magnitude = threshold.abs().clamp(MIN_AXIS_THRESHOLD, 1.0);
+
+    if threshold < 0.0 {
+        value <= -magnitude
+    } else {
+        value >= magnitude
+    }
+}
+
+/// Every input source a [`RecordTrigger`] can read from.
+///
+/// Each one is optional so that apps without e.g. gamepad support still work.
+#[derive(SystemParam)]
+pub struct TriggerInput<'w, 's> {
+    keys: Option<Res<'w, Input<KeyCode>>>,
+    mouse_buttons: Option<Res<'w, Input<MouseButton>>>,
+    gamepad_buttons: Option<Res<'w, Input<GamepadButton>>>,
+    gamepad_axes: Option<Res<'w, Axis<GamepadAxis>>>,
+    gamepads: Option<Res<'w, Gamepads>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's> TriggerInput<'w, 's> {
+    fn gamepads(&self) -> impl Iterator<Item = Gamepad> + '_ {
+        self.gamepads.iter().flat_map(|gamepads| gamepads.iter())
+    }
+
+    fn axis(&self, gamepad: Gamepad, axis: GamepadAxisType) -> f32 {
+        self.gamepad_axes
+            .as_ref()
+            .and_then(|axes| axes.get(GamepadAxis::new(gamepad, axis)))
+            .unwrap_or(0.0)
+    }
+
+    pub fn pressed(&self, trigger: &RecordTrigger) -> bool {
+        match trigger {
+            RecordTrigger::Key(key) => self.keys.as_ref().is_some_and(|keys| keys.pressed(*key)),
+            RecordTrigger::MouseButton(button) => self
+                .mouse_buttons
+                .as_ref()
+                .is_some_and(|buttons| buttons.pressed(*button)),
+            RecordTrigger::GamepadButton(button) => {
+                self.gamepad_buttons.as_ref().is_some_and(|buttons| {
+                    self.gamepads()
+                        .any(|gamepad| buttons.pressed(GamepadButton::new(gamepad, *button)))
+                })
+            }
+            RecordTrigger::GamepadAxis { axis, threshold } => self
+                .gamepads()
+                .any(|gamepad| past_threshold(self.axis(gamepad, *axis), *threshold)),
+        }
+    }
+
+    pub fn any_pressed(&self, triggers: &[RecordTrigger]) -> bool {
+        triggers.iter().any(|trigger| self.pressed(trigger))
+    }
+
+    /// the first input activated this frame, used to bind a new trigger
+    pub fn just_activated(&self, include_mouse: bool) -> Option<RecordTrigger> {
+        if let Some(key) = self
+            .keys
+            .as_ref()
+            .and_then(|keys| keys.get_just_pressed().next())
+        {
+            return Some(RecordTrigger::Key(*key));
+        }
+
+        if include_mouse {
+            if let Some(button) = self
+                .mouse_buttons
+                .as_ref()
+                .and_then(|buttons| buttons.get_just_pressed().next())
+            {
+                return Some(RecordTrigger::MouseButton(*button));
+            }
+        }
+
+        if let Some(button) = self
+            .gamepad_buttons
+            .as_ref()
+            .and_then(|buttons| buttons.get_just_pressed().next())
+        {
+            return Some(RecordTrigger::GamepadButton(button.button_type));
+        }
+
+        self.gamepads().find_map(|gamepad| {
+            GAMEPAD_AXES.into_iter().find_map(|axis| {
+                let value = self.axis(gamepad, axis);
+
+                (value.abs() >= BIND_AXIS_THRESHOLD).then(|| RecordTrigger::GamepadAxis {
+                    axis,
+                    threshold: BIND_AXIS_THRESHOLD.copysign(value),
+                })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn past_threshold_ignores_the_dead_zone() {
+        assert!(past_threshold(0.6, 0.5));
+        assert!(!past_threshold(0.4, 0.5));
+        assert!(past_threshold(-0.6, -0.5));
+        assert!(!past_threshold(0.6, -0.5));
+
+        // a stick at rest never reaches a threshold of 0
+        assert!(!past_threshold(0.0, 0.0));
+        assert!(!past_threshold(0.0, -0.0));
+        assert!(past_threshold(0.2, 0.0));
+    }
+}
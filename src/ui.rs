@@ -0,0 +1,1184 @@
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{
+        self,
+        color::Hsva,
+        plot::{
+            Legend, Line, LineStyle, MarkerShape, Plot, PlotPoint, PlotPoints, PlotUi, Points,
+            Polygon, Text, VLine,
+        },
+    },
+    EguiContext,
+};
+
+#[cfg(feature = "serde")]
+use crate::save::{load_recording, save_recordings, LoadedRecording, SaveFormat};
+use crate::trigger::MIN_AXIS_THRESHOLD;
+use crate::{
+    export_csv, speed_hue, ControlRecording, History, HistoryTimer, MoveVisConfig, RecordMode,
+    RecordTrigger, TrackMovement, TriggerInput,
+};
+
+/// The "Movement" window plotting every [`History`], needs `bevy_egui`'s `EguiPlugin`
+pub struct MoveVisUiPlugin;
+
+impl Plugin for MoveVisUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(plot);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(bevy::ecs::system::SystemParam)]
+struct LoadedRecordings<'w, 's> {
+    commands: Commands<'w, 's>,
+    query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Name,
+            &'static History,
+            &'static LoadedRecording,
+        ),
+        Without<TrackMovement>,
+    >,
+    /// the path typed into the Movement window
+    path: Local<'s, String>,
+}
+
+impl History {
+    /// pair each sample of `values` with its timestamp, ready to be plotted against seconds,
+    /// leaving out the samples without a value
+    fn plot_points<T: Copy + Into<Option<Vec3>>>(
+        &self,
+        values: &[T],
+        f: impl Fn(&Vec3) -> f32,
+    ) -> PlotPoints {
+        self.time
+            .iter()
+            .zip(values.iter())
+            .filter_map(|(&t, &v)| Some([t as f64, f(&v.into()?) as f64]))
+            .collect()
+    }
+}
+
+fn format_secs(secs: f64, _range: &RangeInclusive<f64>) -> String {
+    format!("{secs:.1}s")
+}
+
+type AxisFn = fn(&Vec3) -> f32;
+
+// x, y and z of a Vec3 channel, each plotted as its own line
+const AXES: [(&str, AxisFn); 3] = [
+    ("Horizontal", |v| v.x),
+    ("Vertical", |v| v.y),
+    ("Z", |v| v.z),
+];
+
+/// the axes plotted, Z only with `MoveVisConfig::plot_z`
+fn axes(move_vis_config: &MoveVisConfig) -> &'static [(&'static str, AxisFn)] {
+    if move_vis_config.plot_z {
+        &AXES[..]
+    } else {
+        &AXES[..2]
+    }
+}
+
+// one colour per axis, shared by a plot and its difference plot
+const DISTANCE_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(235, 171, 52),
+    egui::Color32::from_rgb(235, 64, 52),
+    egui::Color32::from_rgb(200, 80, 200),
+];
+const VELOCITY_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(100, 200, 100),
+    egui::Color32::from_rgb(100, 150, 250),
+    egui::Color32::from_rgb(80, 200, 200),
+];
+const ACCELERATION_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(230, 120, 180),
+    egui::Color32::from_rgb(150, 110, 230),
+    egui::Color32::from_rgb(120, 180, 230),
+];
+const JERK_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(200, 200, 90),
+    egui::Color32::from_rgb(160, 120, 80),
+    egui::Color32::from_rgb(140, 200, 160),
+];
+
+// the trajectory and the ideal jump are drawn in the colour of the vertical distance
+const TRAJECTORY_COLOR: egui::Color32 = DISTANCE_COLORS[1];
+
+// the fractions of successive multiples of the golden ratio spread hues evenly around the colour
+// wheel, however many of them there are
+const GOLDEN_RATIO: f32 = 0.618_034;
+
+/// a tracked entity as drawn in the Movement window
+struct Tracked<'a> {
+    entity: Entity,
+    history: &'a History,
+    // rotates the hue of every colour so that the entities can be told apart
+    hue_shift: f32,
+}
+
+impl Tracked<'_> {
+    fn color(&self, color: egui::Color32) -> egui::Color32 {
+        let mut hsva = Hsva::from(color);
+        hsva.h = (hsva.h + self.hue_shift).fract();
+        hsva.into()
+    }
+}
+
+/// the `MoveVisConfig::ghosts` latest completed recordings as `points` of each, unnamed so that
+/// they stay out of the legend, and fainter the older they are
+fn draw_ghosts(
+    plot_ui: &mut PlotUi,
+    move_vis_config: &MoveVisConfig,
+    history: &History,
+    color: egui::Color32,
+    points: impl Fn(&History) -> Option<PlotPoints>,
+) {
+    for (i, ghost) in history
+        .ghosts
+        .iter()
+        .take(move_vis_config.ghosts)
+        .enumerate()
+    {
+        if let Some(points) = points(ghost) {
+            let fade = 0.4 / (i + 1) as f32;
+
+            plot_ui.line(Line::new(points).color(color.linear_multiply(fade)));
+        }
+    }
+}
+
+/// `points` of the pinned reference, if any, as a dashed line
+fn draw_reference(
+    plot_ui: &mut PlotUi,
+    history: &History,
+    color: egui::Color32,
+    name: &str,
+    points: impl Fn(&History) -> Option<PlotPoints>,
+) {
+    if let Some(points) = history.reference.as_deref().and_then(points) {
+        plot_ui.line(
+            Line::new(points)
+                .color(color.linear_multiply(0.6))
+                .style(LineStyle::dashed_loose())
+                .name(name),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plot_vec3<T: Copy + Into<Option<Vec3>>>(
+    ui: &mut egui::Ui,
+    move_vis_config: &MoveVisConfig,
+    tracked: &Tracked,
+    title: &str,
+    values: fn(&History) -> &[T],
+    colors: [egui::Color32; 3],
+    overlay: Vec<Line>,
+    markers: bool,
+) {
+    let axes = axes(move_vis_config);
+
+    Plot::new((tracked.entity, title))
+        .legend(Legend::default())
+        .view_aspect(2.0)
+        .x_axis_formatter(format_secs)
+        .show(ui, |plot_ui| {
+            for (&(_, f), color) in axes.iter().zip(colors) {
+                draw_ghosts(
+                    plot_ui,
+                    move_vis_config,
+                    tracked.history,
+                    tracked.color(color),
+                    |ghost| Some(ghost.plot_points(values(ghost), f)),
+                );
+            }
+
+            for (&(axis, f), color) in axes.iter().zip(colors) {
+                draw_reference(
+                    plot_ui,
+                    tracked.history,
+                    tracked.color(color),
+                    &format!("Reference {axis} {title}"),
+                    |reference| Some(reference.plot_points(values(reference), f)),
+                );
+            }
+
+            for (&(axis, f), color) in axes.iter().zip(colors) {
+                plot_ui.line(
+                    Line::new(tracked.history.plot_points(values(tracked.history), f))
+                        .color(tracked.color(color))
+                        .name(format!("{axis} {title}")),
+                );
+            }
+
+            for line in overlay {
+                plot_ui.line(line);
+            }
+
+            if markers {
+                plot_markers(plot_ui, tracked.history);
+            }
+        });
+}
+
+fn to_color32(color: Color) -> egui::Color32 {
+    let [r, g, b, a] = color.as_rgba_f32().map(|c| (c * 255.0).round() as u8);
+
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
+
+/// every [`crate::Marker`] as a vertical line, labelled at the top of the plot
+fn plot_markers(plot_ui: &mut PlotUi, history: &History) {
+    let top = plot_ui.plot_bounds().max()[1];
+
+    for marker in &history.markers {
+        let color = to_color32(marker.color);
+
+        plot_ui.vline(VLine::new(marker.time).color(color));
+        plot_ui.text(
+            Text::new(PlotPoint::new(marker.time, top), marker.label.as_str())
+                .color(color)
+                .anchor(egui::Align2::LEFT_TOP),
+        );
+    }
+}
+
+/// current minus reference, only drawn once a reference is pinned
+fn plot_difference(
+    ui: &mut egui::Ui,
+    move_vis_config: &MoveVisConfig,
+    tracked: &Tracked,
+    title: &str,
+    values: fn(&History) -> &[Vec3],
+    from_start: bool,
+    colors: [egui::Color32; 3],
+) {
+    let Some(reference) = &tracked.history.reference else {
+        return;
+    };
+
+    let difference = tracked.history.difference(reference, values, from_start);
+
+    let axes = axes(move_vis_config);
+
+    Plot::new((tracked.entity, title))
+        .legend(Legend::default())
+        .view_aspect(2.0)
+        .x_axis_formatter(format_secs)
+        .show(ui, |plot_ui| {
+            for (&(axis, f), color) in axes.iter().zip(colors) {
+                plot_ui.line(
+                    Line::new(
+                        difference
+                            .iter()
+                            .map(|(t, v)| [*t as f64, f(v) as f64])
+                            .collect::<PlotPoints>(),
+                    )
+                    .color(tracked.color(color))
+                    .name(format!("{axis} {title}")),
+                );
+            }
+        });
+}
+
+// how many points each ideal jump is drawn with
+const JUMP_ARC_POINTS: usize = 50;
+
+/// `MoveVisConfig::jump_arc` starting at the take-off of every detected jump
+fn jump_arc_lines(
+    move_vis_config: &MoveVisConfig,
+    tracked: &Tracked,
+    color: egui::Color32,
+) -> Vec<Line> {
+    let Some(jump_arc) = move_vis_config.jump_arc else {
+        return Vec::new();
+    };
+
+    tracked
+        .history
+        .jumps(move_vis_config.airborne_speed)
+        .into_iter()
+        .filter_map(|jump| {
+            let take_off = tracked
+                .history
+                .value_at(&tracked.history.distance, jump.take_off)?;
+
+            let points: PlotPoints = (0..=JUMP_ARC_POINTS)
+                .map(|i| {
+                    let secs = jump_arc.air_time() * i as f32 / JUMP_ARC_POINTS as f32;
+
+                    [
+                        (jump.take_off + secs) as f64,
+                        (take_off.y + jump_arc.height_at(secs)) as f64,
+                    ]
+                })
+                .collect();
+
+            Some(
+                Line::new(points)
+                    .color(tracked.color(color).linear_multiply(0.6))
+                    .style(LineStyle::dotted_dense())
+                    .name("Ideal Jump"),
+            )
+        })
+        .collect()
+}
+
+fn plot_distance(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    plot_vec3(
+        ui,
+        move_vis_config,
+        tracked,
+        "Distance",
+        |history| &history.distance,
+        DISTANCE_COLORS,
+        jump_arc_lines(move_vis_config, tracked, TRAJECTORY_COLOR),
+        true,
+    );
+}
+
+fn path_points(distance: &[Vec3]) -> PlotPoints {
+    distance.iter().map(|d| [d.x as f64, d.y as f64]).collect()
+}
+
+/// [`speed_hue`] as a plot colour
+fn speed_color(speed: f32, max_speed: f32) -> egui::Color32 {
+    Hsva::new(speed_hue(speed, max_speed), 0.8, 0.9, 1.0).into()
+}
+
+/// `distance` as y against x with equal axes, the shape of the path through the level
+fn plot_trajectory(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    let color = tracked.color(TRAJECTORY_COLOR);
+    let history = tracked.history;
+
+    Plot::new((tracked.entity, "Trajectory"))
+        .legend(Legend::default())
+        .view_aspect(2.0)
+        .data_aspect(1.0)
+        .show(ui, |plot_ui| {
+            draw_ghosts(plot_ui, move_vis_config, history, color, |ghost| {
+                Some(path_points(&ghost.distance))
+            });
+
+            draw_reference(
+                plot_ui,
+                history,
+                color,
+                "Reference Trajectory",
+                |reference| Some(path_points(&reference.distance)),
+            );
+
+            if move_vis_config.color_by_speed {
+                let max_speed = history.max_speed();
+
+                // one segment per sample, coloured by the speed at its start
+                for (segment, velocity) in history.distance.windows(2).zip(&history.velocity) {
+                    plot_ui.line(
+                        Line::new(path_points(segment))
+                            .color(speed_color(velocity.length(), max_speed)),
+                    );
+                }
+            } else {
+                plot_ui.line(
+                    Line::new(path_points(&history.distance))
+                        .color(color)
+                        .name("Trajectory"),
+                );
+            }
+
+            for (name, distance, shape) in [
+                ("Start", history.distance.first(), MarkerShape::Circle),
+                ("End", history.distance.last(), MarkerShape::Square),
+            ] {
+                if let Some(distance) = distance {
+                    plot_ui.points(
+                        Points::new(path_points(std::slice::from_ref(distance)))
+                            .shape(shape)
+                            .radius(5.0)
+                            .filled(true)
+                            .color(color)
+                            .name(name),
+                    );
+                }
+            }
+        });
+}
+
+fn plot_velocity(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    plot_vec3(
+        ui,
+        move_vis_config,
+        tracked,
+        "Velocity",
+        |history| &history.velocity,
+        VELOCITY_COLORS,
+        Vec::new(),
+        true,
+    );
+}
+
+fn plot_acceleration(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    plot_vec3(
+        ui,
+        move_vis_config,
+        tracked,
+        "Acceleration",
+        |history| &history.acceleration,
+        ACCELERATION_COLORS,
+        Vec::new(),
+        false,
+    );
+}
+
+fn plot_jerk(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    plot_vec3(
+        ui,
+        move_vis_config,
+        tracked,
+        "Jerk",
+        |history| &history.jerk,
+        JERK_COLORS,
+        Vec::new(),
+        false,
+    );
+}
+
+fn plot_distance_difference(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    plot_difference(
+        ui,
+        move_vis_config,
+        tracked,
+        "Distance Difference",
+        |history| &history.distance,
+        true,
+        DISTANCE_COLORS,
+    );
+}
+
+fn plot_velocity_difference(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    plot_difference(
+        ui,
+        move_vis_config,
+        tracked,
+        "Velocity Difference",
+        |history| &history.velocity,
+        false,
+        VELOCITY_COLORS,
+    );
+}
+
+/// a colour of its own for every state or channel name, the same from frame to frame
+fn name_color(name: &str) -> egui::Color32 {
+    // FNV-1a, stable across runs unlike the std hasher
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    });
+
+    Hsva::new((hash as f32 * GOLDEN_RATIO).fract(), 0.7, 0.8, 1.0).into()
+}
+
+/// `name` without its module paths, `Option<JumpStatus>` rather than
+/// `core::option::Option<movement::player::JumpStatus>`
+fn short_type_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut path = String::new();
+
+    for c in name.chars().chain(std::iter::once('\0')) {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            path.push(c);
+            continue;
+        }
+
+        short.push_str(path.rsplit("::").next().unwrap_or_default());
+        path.clear();
+
+        if c != '\0' {
+            short.push(c);
+        }
+    }
+
+    short
+}
+
+/// `(start, end, value)` of every run of equal values in `lane`, each run lasting until the next
+/// one starts so that there are no gaps between them
+fn spans<'a, T: PartialEq>(time: &[f32], lane: &'a [T]) -> Vec<(f32, f32, &'a T)> {
+    let mut spans = Vec::new();
+    let mut samples = time.iter().zip(lane).peekable();
+
+    while let Some((&start, value)) = samples.next() {
+        let mut end = start;
+        while let Some((&t, _)) = samples.next_if(|(_, next)| *next == value) {
+            end = t;
+        }
+        if let Some((&t, _)) = samples.peek() {
+            end = t;
+        }
+
+        spans.push((start, end, value));
+    }
+
+    spans
+}
+
+/// every `History::states` lane as a band of spans, one colour per state
+fn plot_states(ui: &mut egui::Ui, tracked: &Tracked) {
+    for (key, lane) in &tracked.history.states {
+        plot_band(ui, tracked, ("State", key), &short_type_name(key), lane);
+    }
+}
+
+/// `lane` as a band of spans, one colour per value, labelled with `name`. `id` tells the band apart
+/// from the other bands of the same entity.
+fn plot_band(
+    ui: &mut egui::Ui,
+    tracked: &Tracked,
+    id: impl Hash,
+    name: &str,
+    lane: &[Option<String>],
+) {
+    Plot::new((tracked.entity, id))
+        .legend(Legend::default())
+        .height(60.0)
+        .show_y(false)
+        .y_axis_formatter(|_, _| String::new())
+        .include_y(0.0)
+        .include_y(1.0)
+        .x_axis_formatter(format_secs)
+        .show(ui, |plot_ui| {
+            for (start, end, value) in spans(&tracked.history.time, lane) {
+                let Some(value) = value else {
+                    continue;
+                };
+
+                plot_ui.polygon(
+                    Polygon::new(PlotPoints::new(vec![
+                        [start as f64, 0.0],
+                        [end as f64, 0.0],
+                        [end as f64, 1.0],
+                        [start as f64, 1.0],
+                    ]))
+                    .color(name_color(value))
+                    .fill_alpha(0.6)
+                    .name(format!("{name} {value}")),
+                );
+            }
+        });
+}
+
+/// `History::contacts` as a ground / wall / air band, and the angle of the contact normal
+fn plot_contacts(ui: &mut egui::Ui, tracked: &Tracked) {
+    let history = tracked.history;
+
+    if history.contacts.is_empty() {
+        return;
+    }
+
+    let kinds: Vec<_> = history
+        .contacts
+        .iter()
+        .map(|contact| Some(format!("{:?}", contact.as_ref()?.kind())))
+        .collect();
+
+    plot_band(ui, tracked, "Contact Band", "Contact", &kinds);
+
+    Plot::new((tracked.entity, "Normal Angle"))
+        .legend(Legend::default())
+        .view_aspect(3.0)
+        .include_y(0.0)
+        .include_y(90.0)
+        .x_axis_formatter(format_secs)
+        .y_axis_formatter(|degrees, _| format!("{degrees:.0}°"))
+        .show(ui, |plot_ui| {
+            plot_ui.line(
+                Line::new(
+                    history
+                        .time
+                        .iter()
+                        .zip(&history.contacts)
+                        .filter_map(|(&t, contact)| {
+                            Some([t as f64, contact.as_ref()?.normal_angle()? as f64])
+                        })
+                        .collect::<PlotPoints>(),
+                )
+                .color(tracked.color(egui::Color32::from_rgb(120, 200, 120)))
+                .name("Normal Angle"),
+            );
+        });
+}
+
+/// every `History::inputs` lane as a row, filled while the input was held, like a piano roll
+fn plot_inputs(ui: &mut egui::Ui, tracked: &Tracked) {
+    let history = tracked.history;
+
+    if history.inputs.is_empty() {
+        return;
+    }
+
+    let names: Vec<String> = history.inputs.keys().cloned().collect();
+    let rows = names.len();
+
+    Plot::new((tracked.entity, "Inputs"))
+        .height(20.0 * rows as f32 + 40.0)
+        .include_y(-0.5)
+        .include_y(rows as f64 - 0.5)
+        // one row per input, labelled with its name
+        .y_axis_formatter(move |y, _range| {
+            let row = y.round();
+
+            if (y - row).abs() < f64::EPSILON && row >= 0.0 {
+                names.get(row as usize).cloned().unwrap_or_default()
+            } else {
+                String::new()
+            }
+        })
+        .x_axis_formatter(format_secs)
+        .show(ui, |plot_ui| {
+            for (row, (name, lane)) in history.inputs.iter().enumerate() {
+                let color = tracked.color(name_color(name));
+                let (bottom, top) = (row as f64 - 0.4, row as f64 + 0.4);
+
+                for (start, end, pressed) in spans(&history.time, lane) {
+                    if *pressed != Some(true) {
+                        continue;
+                    }
+
+                    plot_ui.polygon(
+                        Polygon::new(PlotPoints::new(vec![
+                            [start as f64, bottom],
+                            [end as f64, bottom],
+                            [end as f64, top],
+                            [start as f64, top],
+                        ]))
+                        .color(color)
+                        .fill_alpha(0.8),
+                    );
+                }
+            }
+        });
+}
+
+fn channel_points(history: &History, lane: &[Option<f32>]) -> PlotPoints {
+    history
+        .time
+        .iter()
+        .zip(lane)
+        .filter_map(|(&t, value)| Some([t as f64, (*value)? as f64]))
+        .collect()
+}
+
+/// every `History::channels` lane on a plot of its own, which can be collapsed
+fn plot_channels(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    let history = tracked.history;
+
+    for (name, lane) in &history.channels {
+        let color = tracked.color(name_color(name));
+
+        egui::CollapsingHeader::new(name)
+            .id_source((tracked.entity, "Channel", name))
+            .default_open(true)
+            .show(ui, |ui| {
+                Plot::new((tracked.entity, "Channel Plot", name))
+                    .legend(Legend::default())
+                    .view_aspect(3.0)
+                    .x_axis_formatter(format_secs)
+                    .show(ui, |plot_ui| {
+                        let points = |other: &History| {
+                            Some(channel_points(other, other.channels.get(name)?))
+                        };
+
+                        draw_ghosts(plot_ui, move_vis_config, history, color, points);
+
+                        draw_reference(
+                            plot_ui,
+                            history,
+                            color,
+                            &format!("Reference {name}"),
+                            points,
+                        );
+
+                        plot_ui.line(
+                            Line::new(channel_points(history, lane))
+                                .color(color)
+                                .name(name),
+                        );
+                    });
+            });
+    }
+}
+
+/// one row per jump of the current recording
+fn metrics_ui(ui: &mut egui::Ui, move_vis_config: &MoveVisConfig, tracked: &Tracked) {
+    let jumps = tracked.history.jumps(move_vis_config.airborne_speed);
+
+    if jumps.is_empty() {
+        ui.label("No jumps recorded");
+        return;
+    }
+
+    egui::Grid::new((tracked.entity, "Jumps"))
+        .striped(true)
+        .show(ui, |ui| {
+            for header in [
+                "Take-off",
+                "Apex Height",
+                "Time to Apex",
+                "Air Time",
+                "Horizontal Range",
+                "Landing Velocity",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            for jump in jumps {
+                ui.label(format!("{:.2}s", jump.take_off));
+                ui.label(format!("{:.2}", jump.apex_height));
+                ui.label(format!("{:.2}s", jump.time_to_apex));
+                ui.label(format!("{:.2}s", jump.air_time));
+                ui.label(format!("{:.2}", jump.horizontal_range));
+                let v = jump.landing_velocity;
+                ui.label(if move_vis_config.plot_z {
+                    format!("({:.1}, {:.1}, {:.1})", v.x, v.y, v.z)
+                } else {
+                    format!("({:.1}, {:.1})", v.x, v.y)
+                });
+                ui.end_row();
+            }
+        });
+}
+
+enum ReferenceAction {
+    Pin,
+    Unpin,
+    Compare(Box<History>),
+}
+
+/// `candidates` are other recordings, e.g. loaded ones, which can be used as the reference
+fn reference_ui(
+    ui: &mut egui::Ui,
+    history: &History,
+    candidates: &[(String, &History)],
+) -> Option<ReferenceAction> {
+    ui.horizontal(|ui| {
+        let mut action = None;
+
+        if ui
+            .add_enabled(
+                !history.time.is_empty(),
+                egui::Button::new("Pin as Reference"),
+            )
+            .clicked()
+        {
+            action = Some(ReferenceAction::Pin);
+        }
+
+        if !candidates.is_empty() {
+            ui.menu_button("Compare With", |ui| {
+                for (name, candidate) in candidates {
+                    if ui.button(name).clicked() {
+                        action = Some(ReferenceAction::Compare(Box::new(candidate.run())));
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+
+        if history.reference.is_some() && ui.button("Unpin Reference").clicked() {
+            action = Some(ReferenceAction::Unpin);
+        }
+
+        action
+    })
+    .inner
+}
+
+pub fn make_slider<'a, T: egui::emath::Numeric>(
+    caption: &'a str,
+    property: &'a mut T,
+    range: RangeInclusive<T>,
+) -> egui::Slider<'a> {
+    egui::Slider::new(property, range).text(caption)
+}
+
+fn plot_ui(ui: &mut egui::Ui, move_vis_config: &mut MoveVisConfig) {
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut move_vis_config.mode, RecordMode::OneShot, "One Shot");
+        ui.radio_value(&mut move_vis_config.mode, RecordMode::Rolling, "Rolling");
+    });
+
+    let mut secs = move_vis_config.track_duration.as_secs();
+
+    ui.add(make_slider("Duration(secs)", &mut secs, 1..=20));
+
+    move_vis_config.track_duration = Duration::from_secs(secs);
+
+    ui.checkbox(&mut move_vis_config.plot_z, "Show Z");
+
+    ui.checkbox(
+        &mut move_vis_config.color_by_speed,
+        "Colour Trajectory by Speed",
+    );
+
+    ui.add(make_slider("Ghosts", &mut move_vis_config.ghosts, 0..=10));
+
+    #[cfg(feature = "sprites")]
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut move_vis_config.trail, "Trail");
+        ui.add(make_slider(
+            "Lifetime(secs)",
+            &mut move_vis_config.trail_lifetime,
+            0.1..=20.0,
+        ));
+        ui.add(make_slider(
+            "Size",
+            &mut move_vis_config.trail_size,
+            1.0..=10.0,
+        ));
+    });
+
+    #[cfg(feature = "sprites")]
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut move_vis_config.velocity_arrows, "Velocity Arrows");
+        ui.add(make_slider(
+            "Every",
+            &mut move_vis_config.arrow_every,
+            1..=30,
+        ));
+        ui.add(make_slider(
+            "Scale",
+            &mut move_vis_config.arrow_scale,
+            0.01..=1.0,
+        ));
+    });
+}
+
+fn triggers_ui(
+    ui: &mut egui::Ui,
+    triggers: &mut Vec<RecordTrigger>,
+    trigger_input: &TriggerInput,
+    binding: &mut bool,
+) {
+    egui::CollapsingHeader::new("Triggers").show(ui, |ui| {
+        let mut removed = None;
+
+        for (i, trigger) in triggers.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(trigger.to_string());
+
+                if let RecordTrigger::GamepadAxis { threshold, .. } = trigger {
+                    // the sign picks the direction, only the magnitude is slid
+                    let sign = if *threshold < 0.0 { -1.0 } else { 1.0 };
+                    let mut magnitude = threshold.abs();
+
+                    ui.add(make_slider(
+                        "Threshold",
+                        &mut magnitude,
+                        MIN_AXIS_THRESHOLD..=1.0,
+                    ));
+                    *threshold = sign * magnitude;
+                }
+
+                if ui.small_button("x").clicked() {
+                    removed = Some(i);
+                }
+            });
+        }
+
+        if let Some(i) = removed {
+            triggers.remove(i);
+        }
+
+        if *binding {
+            ui.horizontal(|ui| {
+                ui.label("Press a key, mouse button or gamepad input...");
+
+                if ui.button("Cancel").clicked() {
+                    *binding = false;
+                }
+            });
+
+            // clicks on the Movement window itself should not be bound
+            let include_mouse = !ui.ctx().is_pointer_over_area();
+
+            if let Some(trigger) = trigger_input.just_activated(include_mouse) {
+                if !triggers.contains(&trigger) {
+                    triggers.push(trigger);
+                }
+                *binding = false;
+            }
+        } else if ui.button("Add Trigger").clicked() {
+            *binding = true;
+        }
+    });
+}
+
+fn entity_title(entity: Entity, name: Option<&Name>) -> String {
+    name.map_or_else(|| format!("{entity:?}"), |name| name.to_string())
+}
+
+/// the entity's id is already part of every file name, an unnamed entity needs no more
+fn file_name(name: Option<&Name>) -> String {
+    name.map_or_else(|| "Entity".to_string(), |name| name.to_string())
+}
+
+type TrackedQuery<'w, 's, 'a> = Query<
+    'w,
+    's,
+    (Entity, Option<&'a Name>, &'a mut History, &'a HistoryTimer),
+    With<TrackMovement>,
+>;
+
+fn freeze_ui(
+    ui: &mut egui::Ui,
+    query: &TrackedQuery,
+    control_recording: &mut EventWriter<ControlRecording>,
+) {
+    let frozen = query
+        .iter()
+        .all(|(.., history_timer)| history_timer.0.paused());
+
+    if frozen {
+        if ui.button("Resume").clicked() {
+            control_recording.send_batch(
+                query
+                    .iter()
+                    .map(|(entity, ..)| ControlRecording::Start { entity }),
+            );
+        }
+    } else if ui.button("Freeze").clicked() {
+        control_recording.send_batch(
+            query
+                .iter()
+                .map(|(entity, ..)| ControlRecording::Stop { entity }),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plot(
+    mut move_vis_config: ResMut<MoveVisConfig>,
+    mut egui_context: ResMut<EguiContext>,
+    mut binding_trigger: Local<bool>,
+    trigger_input: TriggerInput,
+    mut control_recording: EventWriter<ControlRecording>,
+    mut query: TrackedQuery,
+    #[cfg(feature = "serde")] mut loaded_recordings: LoadedRecordings,
+) {
+    egui::Window::new("Movement").show(egui_context.ctx_mut(), |ui| {
+        plot_ui(ui, &mut move_vis_config);
+
+        if move_vis_config.mode == RecordMode::Rolling {
+            freeze_ui(ui, &query, &mut control_recording);
+        }
+
+        triggers_ui(
+            ui,
+            &mut move_vis_config.triggers,
+            &trigger_input,
+            &mut binding_trigger,
+        );
+
+        if ui.button("Export CSV").clicked() {
+            export_csv(
+                &move_vis_config.export_dir,
+                query
+                    .iter()
+                    .map(|(entity, name, history, _)| (entity, file_name(name), history)),
+            );
+        }
+
+        #[cfg(feature = "serde")]
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut move_vis_config.save_format, SaveFormat::Ron, "RON");
+            ui.radio_value(&mut move_vis_config.save_format, SaveFormat::Json, "JSON");
+
+            if ui.button("Save Recordings").clicked() {
+                save_recordings(
+                    &move_vis_config,
+                    query
+                        .iter()
+                        .map(|(entity, name, history, _)| (entity, file_name(name), history)),
+                );
+            }
+        });
+
+        #[cfg(feature = "serde")]
+        load_ui(ui, &mut loaded_recordings);
+
+        #[cfg(feature = "serde")]
+        let candidates: Vec<_> = loaded_recordings
+            .query
+            .iter()
+            .map(|(_, name, history, _)| (name.to_string(), history))
+            .collect();
+        #[cfg(not(feature = "serde"))]
+        let candidates = Vec::new();
+
+        for (i, (entity, name, mut history, _)) in query.iter_mut().enumerate() {
+            let tracked = Tracked {
+                entity,
+                history: &history,
+                hue_shift: (i as f32 * GOLDEN_RATIO).fract(),
+            };
+
+            let title = entity_title(entity, name);
+
+            let reference_action = egui::CollapsingHeader::new(title)
+                .id_source(entity)
+                .default_open(true)
+                .show(ui, |ui| {
+                    let reference_action = reference_ui(ui, tracked.history, &candidates);
+
+                    plot_distance(ui, &move_vis_config, &tracked);
+
+                    plot_distance_difference(ui, &move_vis_config, &tracked);
+
+                    plot_trajectory(ui, &move_vis_config, &tracked);
+
+                    plot_velocity(ui, &move_vis_config, &tracked);
+
+                    plot_inputs(ui, &tracked);
+
+                    plot_velocity_difference(ui, &move_vis_config, &tracked);
+
+                    plot_acceleration(ui, &move_vis_config, &tracked);
+
+                    plot_jerk(ui, &move_vis_config, &tracked);
+
+                    plot_channels(ui, &move_vis_config, &tracked);
+
+                    plot_states(ui, &tracked);
+
+                    plot_contacts(ui, &tracked);
+
+                    metrics_ui(ui, &move_vis_config, &tracked);
+
+                    reference_action
+                })
+                .body_returned
+                .flatten();
+
+            match reference_action {
+                Some(ReferenceAction::Pin) => history.pin_reference(),
+                Some(ReferenceAction::Unpin) => history.reference = None,
+                Some(ReferenceAction::Compare(reference)) => history.reference = Some(reference),
+                None => (),
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        loaded_recordings_ui(
+            ui,
+            &move_vis_config,
+            &mut loaded_recordings,
+            query.iter().len(),
+        );
+    });
+}
+
+#[cfg(feature = "serde")]
+fn load_ui(ui: &mut egui::Ui, loaded_recordings: &mut LoadedRecordings) {
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(&mut *loaded_recordings.path);
+
+        if ui.button("Load Recording").clicked() {
+            let path = loaded_recordings.path.clone();
+
+            if let Err(e) = load_recording(&mut loaded_recordings.commands, &path) {
+                error!("failed to load {path}: {e}");
+            }
+        }
+    });
+}
+
+/// loaded recordings are read-only, they can only be looked at or closed
+#[cfg(feature = "serde")]
+fn loaded_recordings_ui(
+    ui: &mut egui::Ui,
+    move_vis_config: &MoveVisConfig,
+    loaded_recordings: &mut LoadedRecordings,
+    hue_offset: usize,
+) {
+    for (i, (entity, name, history, loaded_recording)) in loaded_recordings.query.iter().enumerate()
+    {
+        let tracked = Tracked {
+            entity,
+            history,
+            hue_shift: ((hue_offset + i) as f32 * GOLDEN_RATIO).fract(),
+        };
+
+        egui::CollapsingHeader::new(name.to_string())
+            .id_source(entity)
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::CollapsingHeader::new("Settings")
+                    .id_source((entity, "Settings"))
+                    .show(ui, |ui| {
+                        ui.label(format!("{:#?}", loaded_recording.config));
+
+                        if let Some(settings) = &loaded_recording.settings {
+                            ui.label(
+                                ron::ser::to_string_pretty(settings, default())
+                                    .unwrap_or_else(|e| e.to_string()),
+                            );
+                        }
+                    });
+
+                if ui.button("Close").clicked() {
+                    loaded_recordings.commands.entity(entity).despawn();
+                }
+
+                plot_distance(ui, move_vis_config, &tracked);
+
+                plot_trajectory(ui, move_vis_config, &tracked);
+
+                plot_velocity(ui, move_vis_config, &tracked);
+
+                plot_inputs(ui, &tracked);
+
+                plot_acceleration(ui, move_vis_config, &tracked);
+
+                plot_jerk(ui, move_vis_config, &tracked);
+
+                plot_channels(ui, move_vis_config, &tracked);
+
+                plot_states(ui, &tracked);
+
+                plot_contacts(ui, &tracked);
+
+                metrics_ui(ui, move_vis_config, &tracked);
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_type_names() {
+        assert_eq!(
+            short_type_name("movement::player::JumpStatus"),
+            "JumpStatus"
+        );
+        assert_eq!(
+            short_type_name("core::option::Option<movement::player::JumpStatus>"),
+            "Option<JumpStatus>"
+        );
+        assert_eq!(
+            short_type_name("(a::Left, alloc::vec::Vec<b::Right>)"),
+            "(Left, Vec<Right>)"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{speed_hue, History, MoveVisConfig};
+
+/// Draws every [`History`] in the game world as sprites, on top of the level it was recorded in.
+/// Needs a 2D camera and bevy's `SpritePlugin`, both part of a usual 2D game.
+pub struct MoveVisWorldPlugin;
+
+impl Plugin for MoveVisWorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(draw_trail).add_system(draw_velocity_arrows);
+    }
+}
+
+// drawn above the level so that the trail is never hidden behind a platform
+const TRAIL_Z: f32 = 100.0;
+
+// arrows are drawn above the trail
+const ARROW_Z: f32 = TRAIL_Z + 1.0;
+
+const ARROW_WIDTH: f32 = 1.5;
+
+/// one breadcrumb of a trail
+#[derive(Component, Debug)]
+struct TrailDot;
+
+/// the shaft or head of a velocity arrow
+#[derive(Component, Debug)]
+struct VelocityArrow;
+
+/// [`speed_hue`] as a sprite colour
+fn speed_color(speed: f32, max_speed: f32, alpha: f32) -> Color {
+    Color::hsla(360.0 * speed_hue(speed, max_speed), 0.8, 0.5, alpha)
+}
+
+type PooledSprite<'a> = (Mut<'a, Sprite>, Mut<'a, Transform>, Mut<'a, Visibility>);
+
+/// show `sprite` at `transform` with the next unused sprite of `pool`, spawning one marked with
+/// `marker` when the pool runs out
+fn place_sprite<'a>(
+    commands: &mut Commands,
+    pool: &mut impl Iterator<Item = PooledSprite<'a>>,
+    marker: impl Component,
+    sprite: Sprite,
+    transform: Transform,
+) {
+    match pool.next() {
+        Some((mut pooled_sprite, mut pooled_transform, mut visibility)) => {
+            *pooled_sprite = sprite;
+            *pooled_transform = transform;
+            visibility.is_visible = true;
+        }
+        None => {
+            commands.spawn((
+                SpriteBundle {
+                    sprite,
+                    transform,
+                    ..default()
+                },
+                marker,
+            ));
+        }
+    }
+}
+
+/// whatever is left over is kept for later frames, just out of sight
+fn hide_sprites<'a>(pool: impl Iterator<Item = PooledSprite<'a>>) {
+    for (.., mut visibility) in pool {
+        visibility.is_visible = false;
+    }
+}
+
+fn draw_trail(
+    mut commands: Commands,
+    time: Res<Time>,
+    move_vis_config: Res<MoveVisConfig>,
+    // the latest sample of each history, and when it was recorded
+    mut latest_samples: Local<HashMap<Entity, (f32, f32)>>,
+    histories: Query<(Entity, &History)>,
+    mut dots: Query<(&mut Sprite, &mut Transform, &mut Visibility), With<TrailDot>>,
+) {
+    let now = time.elapsed_seconds();
+    latest_samples.retain(|&entity, _| histories.contains(entity));
+
+    let mut dots = dots.iter_mut();
+
+    if move_vis_config.trail {
+        for (entity, history) in histories.iter() {
+            let Some(&latest) = history.time.last() else {
+                continue;
+            };
+
+            let (sample, recorded_at) = latest_samples.entry(entity).or_insert((latest, now));
+            if *sample != latest {
+                *sample = latest;
+                *recorded_at = now;
+            }
+            // time keeps going once the recording has stopped, so that a finished trail fades too
+            let since_latest = now - *recorded_at;
+
+            let max_speed = history.max_speed();
+
+            for ((&t, distance), velocity) in history
+                .time
+                .iter()
+                .zip(&history.distance)
+                .zip(&history.velocity)
+            {
+                // the older a breadcrumb, the fainter, until it disappears
+                let alpha = 1.0 - (since_latest + latest - t) / move_vis_config.trail_lifetime;
+
+                if alpha <= 0.0 {
+                    continue;
+                }
+
+                place_sprite(
+                    &mut commands,
+                    &mut dots,
+                    TrailDot,
+                    Sprite {
+                        color: speed_color(velocity.length(), max_speed, alpha),
+                        custom_size: Some(Vec2::splat(move_vis_config.trail_size)),
+                        ..default()
+                    },
+                    Transform::from_translation(distance.truncate().extend(TRAIL_Z)),
+                );
+            }
+        }
+    }
+
+    hide_sprites(dots);
+}
+
+fn draw_velocity_arrows(
+    mut commands: Commands,
+    move_vis_config: Res<MoveVisConfig>,
+    histories: Query<&History>,
+    mut arrows: Query<(&mut Sprite, &mut Transform, &mut Visibility), With<VelocityArrow>>,
+) {
+    let mut arrows = arrows.iter_mut();
+
+    if move_vis_config.velocity_arrows {
+        for history in histories.iter() {
+            let max_speed = history.max_speed();
+
+            for (distance, velocity) in history
+                .distance
+                .iter()
+                .zip(&history.velocity)
+                .step_by(move_vis_config.arrow_every.max(1))
+            {
+                let arrow = velocity.truncate() * move_vis_config.arrow_scale;
+                let length = arrow.length();
+
+                if length <= f32::EPSILON {
+                    continue;
+                }
+
+                let color = speed_color(velocity.length(), max_speed, 1.0);
+                let rotation = Quat::from_rotation_z(arrow.y.atan2(arrow.x));
+
+                // the shaft, centred halfway along the arrow
+                place_sprite(
+                    &mut commands,
+                    &mut arrows,
+                    VelocityArrow,
+                    Sprite {
+                        color,
+                        custom_size: Some(Vec2::new(length, ARROW_WIDTH)),
+                        ..default()
+                    },
+                    Transform::from_translation(
+                        (distance.truncate() + arrow / 2.0).extend(ARROW_Z),
+                    )
+                    .with_rotation(rotation),
+                );
+
+                // the head, a square turned into a diamond at the tip
+                place_sprite(
+                    &mut commands,
+                    &mut arrows,
+                    VelocityArrow,
+                    Sprite {
+                        color,
+                        custom_size: Some(Vec2::splat(ARROW_WIDTH * 3.0)),
+                        ..default()
+                    },
+                    Transform::from_translation((distance.truncate() + arrow).extend(ARROW_Z))
+                        .with_rotation(rotation * Quat::from_rotation_z(FRAC_PI_4)),
+                );
+            }
+        }
+    }
+
+    hide_sprites(arrows);
+}